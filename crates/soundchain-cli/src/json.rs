@@ -0,0 +1,92 @@
+//! JSON views of decoded accounts
+
+use serde_json::{json, Value};
+use soundchain_sdk::{
+    math, Auction, BuyerRestriction, Listing, ListingStatus, ListingType, Pubkey, ScidRecord, TokenGate,
+};
+
+pub fn listing(address: &Pubkey, listing: &Listing) -> Value {
+    json!({
+        "address": address.to_string(),
+        "seller": listing.seller.to_string(),
+        "nft_mint": listing.nft_mint.to_string(),
+        "payment_mint": listing.payment_mint.to_string(),
+        "price": listing.price,
+        "listing_type": listing_type(listing.listing_type),
+        "status": listing_status(listing.status),
+        "created_at": listing.created_at,
+        "expires_at": listing.expires_at,
+        "scid": listing.scid,
+        "buyer": listing.buyer.map(|buyer| buyer.to_string()),
+        "sold_at": listing.sold_at,
+        "version": listing.version,
+        "usd_price": listing.usd_price,
+        "restriction": listing.restriction.map(restriction),
+        "token_gate": listing.token_gate.map(token_gate),
+    })
+}
+
+pub fn auction(address: &Pubkey, auction: &Auction) -> Value {
+    json!({
+        "address": address.to_string(),
+        "listing": auction.listing.to_string(),
+        "current_bid": auction.current_bid,
+        "current_bidder": auction.current_bidder.to_string(),
+        "bid_count": auction.bid_count,
+        "reserve_met": auction.reserve_met,
+        "referrer": auction.referrer.map(|referrer| referrer.to_string()),
+        "min_bid": math::min_bid(auction),
+        "version": auction.version,
+    })
+}
+
+pub fn scid_record(address: &Pubkey, record: &ScidRecord) -> Value {
+    json!({
+        "address": address.to_string(),
+        "scid": record.scid,
+        "owner": record.owner.to_string(),
+        "token_id": record.token_id,
+        "nft_mint": record.nft_mint.to_string(),
+        "metadata_hash": record.metadata_hash,
+        "artist_hash": record.artist_hash,
+        "year": record.year,
+        "sequence": record.sequence,
+        "registered_at": record.registered_at,
+        "active": record.active,
+        "cross_chain_verified": record.cross_chain_verified,
+        "source_chain": record.source_chain,
+        "source_tx_hash": hex::encode(record.source_tx_hash),
+        "version": record.version,
+    })
+}
+
+fn restriction(restriction: BuyerRestriction) -> Value {
+    match restriction {
+        BuyerRestriction::Buyer(buyer) => json!({ "buyer": buyer.to_string() }),
+        BuyerRestriction::Allowlist { root } => json!({ "allowlist_root": hex::encode(root) }),
+    }
+}
+
+fn token_gate(token_gate: TokenGate) -> Value {
+    match token_gate {
+        TokenGate::MinBalance { mint, amount } => json!({ "mint": mint.to_string(), "min_balance": amount }),
+        TokenGate::Collection(collection) => json!({ "collection": collection.to_string() }),
+    }
+}
+
+fn listing_type(listing_type: ListingType) -> &'static str {
+    match listing_type {
+        ListingType::FixedPrice => "fixed_price",
+        ListingType::Auction => "auction",
+        ListingType::MakeOffer => "make_offer",
+    }
+}
+
+fn listing_status(status: ListingStatus) -> &'static str {
+    match status {
+        ListingStatus::Active => "active",
+        ListingStatus::Sold => "sold",
+        ListingStatus::Cancelled => "cancelled",
+        ListingStatus::Expired => "expired",
+    }
+}
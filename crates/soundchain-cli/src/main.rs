@@ -0,0 +1,299 @@
+//! SoundChain admin and operator CLI
+//!
+//! Sends admin instructions for the marketplace and SCid registry, or with
+//! `--dry-run` prints the unsigned transaction so a multisig can sign it.
+//! Pass `--nonce-account` with `--dry-run` to build it on a durable nonce,
+//! since a recent blockhash expires long before every member has signed.
+
+use std::error::Error;
+
+use base64::Engine;
+use clap::{Parser, Subcommand};
+use solana_client::nonce_utils;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::Transaction;
+use soundchain_sdk::{marketplace, pda, scid, Instruction, Pubkey};
+
+mod json;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(name = "soundchain", about = "Admin and operator CLI for the SoundChain Solana programs")]
+struct Cli {
+    /// RPC endpoint
+    #[arg(long, global = true, default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Keypair that signs and pays for transactions
+    #[arg(long, global = true, default_value = "~/.config/solana/id.json")]
+    keypair: String,
+    /// Print the unsigned transaction (base64) instead of sending it
+    #[arg(long, global = true)]
+    dry_run: bool,
+    /// Signing authority for --dry-run, e.g. a multisig vault; defaults to the keypair
+    #[arg(long, global = true)]
+    signer: Option<Pubkey>,
+    /// Durable nonce account for --dry-run; its blockhash doesn't expire
+    #[arg(long, global = true, requires = "dry_run")]
+    nonce_account: Option<Pubkey>,
+    /// Authority of --nonce-account; defaults to the authority stored in it
+    #[arg(long, global = true, requires = "nonce_account")]
+    nonce_authority: Option<Pubkey>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Marketplace admin
+    #[command(subcommand)]
+    Marketplace(MarketplaceCommand),
+    /// SCid registry admin
+    #[command(subcommand)]
+    Registry(RegistryCommand),
+    /// SCid moderation and cross-chain verification
+    #[command(subcommand)]
+    Scid(ScidCommand),
+    /// Print an account as JSON
+    #[command(subcommand)]
+    Show(ShowCommand),
+}
+
+#[derive(Subcommand)]
+enum MarketplaceCommand {
+    /// Create the marketplace account
+    Initialize {
+        /// Defaults to the fee collector program's config PDA
+        #[arg(long)]
+        fee_collector: Option<Pubkey>,
+        /// Platform fee in basis points
+        #[arg(long)]
+        platform_fee: u16,
+    },
+    /// Propose a platform fee (basis points), applied by execute-fee after the timelock
+    ProposeFee { fee: u16 },
+    /// Apply the pending platform fee
+    ExecuteFee,
+    /// Drop the pending platform fee
+    CancelFee,
+    /// Pause or unpause trading
+    SetPaused {
+        #[arg(action = clap::ArgAction::Set)]
+        paused: bool,
+    },
+    /// Accept a payment mint for USD listings, priced by a Pyth price update account
+    SetPriceFeed {
+        payment_mint: Pubkey,
+        #[arg(long)]
+        oracle: Pubkey,
+        /// 32-byte Pyth feed id, hex encoded
+        #[arg(long, value_parser = parse_hex32)]
+        feed_id: [u8; 32],
+        /// Max oracle price age in seconds
+        #[arg(long, default_value_t = 60)]
+        max_staleness: i64,
+        /// Max confidence interval in basis points of the price
+        #[arg(long, default_value_t = 100)]
+        max_confidence: u16,
+    },
+    /// Stop accepting a payment mint for USD listings
+    RemovePriceFeed { payment_mint: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum RegistryCommand {
+    /// Create the registry account
+    Initialize {
+        /// Defaults to the fee collector program's config PDA
+        #[arg(long)]
+        fee_collector: Option<Pubkey>,
+    },
+    /// Propose a registration fee (lamports), applied by execute-fee after the timelock
+    ProposeFee { fee: u64 },
+    /// Apply the pending registration fee
+    ExecuteFee,
+    /// Drop the pending registration fee
+    CancelFee,
+    /// Pause or unpause registrations
+    SetPaused {
+        #[arg(action = clap::ArgAction::Set)]
+        paused: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ScidCommand {
+    /// Revoke an SCid as its owner, or as a moderator with --moderator
+    Revoke {
+        scid: String,
+        #[arg(long)]
+        moderator: bool,
+    },
+    /// Mark an SCid as registered on another chain
+    VerifyCrossChain {
+        scid: String,
+        #[arg(long)]
+        source_chain: u16,
+        /// 32-byte source transaction hash, hex encoded
+        #[arg(long, value_parser = parse_hex32)]
+        source_tx_hash: [u8; 32],
+    },
+}
+
+#[derive(Subcommand)]
+enum ShowCommand {
+    /// Listing for an NFT mint
+    Listing { nft_mint: Pubkey },
+    /// Auction for an NFT mint
+    Auction { nft_mint: Pubkey },
+    /// SCid record
+    Scid { scid: String },
+}
+
+fn main() {
+    if let Err(err) = run(Cli::parse()) {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let client = RpcClient::new_with_commitment(cli.url.clone(), CommitmentConfig::confirmed());
+
+    let instruction = match &cli.command {
+        Command::Show(command) => return show(&client, command),
+        Command::Marketplace(command) => {
+            let signer = signer(&cli)?;
+            match command {
+                MarketplaceCommand::Initialize { fee_collector, platform_fee } => {
+                    marketplace::initialize(&signer, fee_collector_or_default(*fee_collector), *platform_fee)
+                }
+                MarketplaceCommand::ProposeFee { fee } => marketplace::propose_fee(&signer, *fee),
+                MarketplaceCommand::ExecuteFee => marketplace::execute_fee(&signer),
+                MarketplaceCommand::CancelFee => marketplace::cancel_fee(&signer),
+                MarketplaceCommand::SetPaused { paused } => marketplace::set_paused(&signer, *paused),
+                MarketplaceCommand::SetPriceFeed { payment_mint, oracle, feed_id, max_staleness, max_confidence } => {
+                    marketplace::set_price_feed(&signer, payment_mint, *oracle, *feed_id, *max_staleness, *max_confidence)
+                }
+                MarketplaceCommand::RemovePriceFeed { payment_mint } => {
+                    marketplace::remove_price_feed(&signer, payment_mint)
+                }
+            }
+        }
+        Command::Registry(command) => {
+            let signer = signer(&cli)?;
+            match command {
+                RegistryCommand::Initialize { fee_collector } => {
+                    scid::initialize(&signer, fee_collector_or_default(*fee_collector))
+                }
+                RegistryCommand::ProposeFee { fee } => scid::propose_fee(&signer, *fee),
+                RegistryCommand::ExecuteFee => scid::execute_fee(&signer),
+                RegistryCommand::CancelFee => scid::cancel_fee(&signer),
+                RegistryCommand::SetPaused { paused } => scid::set_paused(&signer, *paused),
+            }
+        }
+        Command::Scid(command) => {
+            let signer = signer(&cli)?;
+            match command {
+                ScidCommand::Revoke { scid, moderator } => scid::revoke(&signer, scid, *moderator),
+                ScidCommand::VerifyCrossChain { scid, source_chain, source_tx_hash } => {
+                    scid::verify_cross_chain(&signer, scid, *source_chain, *source_tx_hash)
+                }
+            }
+        }
+    };
+
+    send(&cli, &client, instruction)
+}
+
+/// Key the instruction is built for: --signer in dry-run mode, otherwise the keypair
+fn signer(cli: &Cli) -> Result<Pubkey> {
+    match (cli.dry_run, cli.signer) {
+        (true, Some(signer)) => Ok(signer),
+        (false, Some(_)) => Err("--signer only applies with --dry-run".into()),
+        (_, None) => Ok(keypair(cli)?.pubkey()),
+    }
+}
+
+fn keypair(cli: &Cli) -> Result<Keypair> {
+    let path = match cli.keypair.strip_prefix("~/") {
+        Some(rest) => format!("{}/{rest}", std::env::var("HOME")?),
+        None => cli.keypair.clone(),
+    };
+    read_keypair_file(&path).map_err(|err| format!("reading keypair {path}: {err}").into())
+}
+
+/// Nonce advance instruction and stored blockhash for --nonce-account
+fn durable_nonce(cli: &Cli, client: &RpcClient, nonce_account: &Pubkey) -> Result<(Instruction, Hash)> {
+    let account = nonce_utils::get_account(client, nonce_account)?;
+    let data = nonce_utils::data_from_account(&account)?;
+    let authority = cli.nonce_authority.unwrap_or(data.authority);
+    if authority != data.authority {
+        let stored = data.authority;
+        return Err(format!("nonce account {nonce_account} is controlled by {stored}, not {authority}").into());
+    }
+    Ok((system_instruction::advance_nonce_account(nonce_account, &authority), data.blockhash()))
+}
+
+fn send(cli: &Cli, client: &RpcClient, instruction: Instruction) -> Result<()> {
+    if cli.dry_run {
+        let payer = signer(cli)?;
+        // The nonce advance must be the first instruction for the runtime to accept its blockhash
+        let (instructions, blockhash) = match &cli.nonce_account {
+            Some(nonce_account) => {
+                let (advance, blockhash) = durable_nonce(cli, client, nonce_account)?;
+                (vec![advance, instruction], blockhash)
+            }
+            None => (vec![instruction], client.get_latest_blockhash()?),
+        };
+        let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer));
+        transaction.message.recent_blockhash = blockhash;
+        let serialized = bincode::serialize(&transaction)?;
+        println!("{}", base64::engine::general_purpose::STANDARD.encode(serialized));
+        return Ok(());
+    }
+
+    let blockhash = client.get_latest_blockhash()?;
+    let keypair = keypair(cli)?;
+    let transaction =
+        Transaction::new_signed_with_payer(&[instruction], Some(&keypair.pubkey()), &[&keypair], blockhash);
+    let signature = client.send_and_confirm_transaction(&transaction)?;
+    println!("{signature}");
+    Ok(())
+}
+
+fn show(client: &RpcClient, command: &ShowCommand) -> Result<()> {
+    let value = match command {
+        ShowCommand::Listing { nft_mint } => {
+            let address = pda::find_listing_address(nft_mint).0;
+            let listing = soundchain_sdk::decode_listing(&client.get_account_data(&address)?)?;
+            json::listing(&address, &listing)
+        }
+        ShowCommand::Auction { nft_mint } => {
+            let listing = pda::find_listing_address(nft_mint).0;
+            let address = pda::find_auction_address(&listing).0;
+            let auction = soundchain_sdk::decode_auction(&client.get_account_data(&address)?)?;
+            json::auction(&address, &auction)
+        }
+        ShowCommand::Scid { scid } => {
+            let address = pda::find_scid_address(scid).0;
+            let record = soundchain_sdk::decode_scid_record(&client.get_account_data(&address)?)?;
+            json::scid_record(&address, &record)
+        }
+    };
+    println!("{}", serde_json::to_string_pretty(&value)?);
+    Ok(())
+}
+
+fn fee_collector_or_default(fee_collector: Option<Pubkey>) -> Pubkey {
+    fee_collector.unwrap_or_else(|| pda::find_fee_collector_address().0)
+}
+
+fn parse_hex32(value: &str) -> std::result::Result<[u8; 32], String> {
+    let bytes = hex::decode(value.trim_start_matches("0x")).map_err(|err| err.to_string())?;
+    <[u8; 32]>::try_from(bytes).map_err(|_| "expected 32 bytes".to_string())
+}
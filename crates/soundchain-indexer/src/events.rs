@@ -0,0 +1,89 @@
+//! Anchor event decoding from transaction logs
+//!
+//! Anchor's `emit!` logs `Program data: <base64>`, where the payload is the
+//! event's 8-byte discriminator followed by its Borsh encoding. Events are only
+//! taken from log lines written while one of our programs is the innermost
+//! invocation, so another program cannot forge them.
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::Engine;
+use soundchain_sdk::{Pubkey, MARKETPLACE_PROGRAM_ID, SCID_PROGRAM_ID};
+
+use soundchain_marketplace::{BidPlaced, ListingCancelled, ListingCreated, ListingExpired, ListingSold};
+use soundchain_scid::{CrossChainVerified, ScidRegistered, ScidRevoked, ScidTransferred};
+
+/// Indexed program events
+pub enum Event {
+    ListingCreated(ListingCreated),
+    ListingSold(ListingSold),
+    BidPlaced(BidPlaced),
+    ListingCancelled(ListingCancelled),
+    ListingExpired(ListingExpired),
+    ScidRegistered(ScidRegistered),
+    ScidTransferred(ScidTransferred),
+    CrossChainVerified(CrossChainVerified),
+    ScidRevoked(ScidRevoked),
+}
+
+/// Decode the indexed events in a transaction's log messages, in log order
+pub fn parse_logs(logs: &[String]) -> Vec<Event> {
+    let mut invocations: Vec<Pubkey> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+
+        if let Some(data) = rest.strip_prefix("data: ") {
+            let Ok(data) = base64::engine::general_purpose::STANDARD.decode(data) else {
+                continue;
+            };
+            let event = match invocations.last() {
+                Some(program) if *program == MARKETPLACE_PROGRAM_ID => decode_marketplace(&data),
+                Some(program) if *program == SCID_PROGRAM_ID => decode_scid(&data),
+                _ => None,
+            };
+            events.extend(event);
+            continue;
+        }
+
+        // "Program <id> invoke [n]", "Program <id> success", "Program <id> failed: ..."
+        let mut parts = rest.splitn(2, ' ');
+        let (Some(program), Some(status)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let Ok(program) = program.parse::<Pubkey>() else {
+            continue;
+        };
+        if status.starts_with("invoke [") {
+            invocations.push(program);
+        } else if status == "success" || status.starts_with("failed") {
+            invocations.pop();
+        }
+    }
+
+    events
+}
+
+fn decode_marketplace(data: &[u8]) -> Option<Event> {
+    decode(data)
+        .map(Event::ListingCreated)
+        .or_else(|| decode(data).map(Event::ListingSold))
+        .or_else(|| decode(data).map(Event::BidPlaced))
+        .or_else(|| decode(data).map(Event::ListingCancelled))
+        .or_else(|| decode(data).map(Event::ListingExpired))
+}
+
+fn decode_scid(data: &[u8]) -> Option<Event> {
+    decode(data)
+        .map(Event::ScidRegistered)
+        .or_else(|| decode(data).map(Event::ScidTransferred))
+        .or_else(|| decode(data).map(Event::CrossChainVerified))
+        .or_else(|| decode(data).map(Event::ScidRevoked))
+}
+
+fn decode<T: AnchorDeserialize + Discriminator>(data: &[u8]) -> Option<T> {
+    let payload = data.strip_prefix(&T::DISCRIMINATOR[..])?;
+    T::deserialize(&mut &payload[..]).ok()
+}
@@ -0,0 +1,34 @@
+//! SoundChain event indexer
+//!
+//! Decodes Marketplace and SCid program events from transaction logs and
+//! stores them in SQLite. Transactions come from RPC (`source`) or from
+//! recorded fixtures, and go through the same `Store::index` path either way.
+
+use serde::Deserialize;
+use soundchain_sdk::{MARKETPLACE_PROGRAM_ID, SCID_PROGRAM_ID};
+
+pub mod events;
+pub mod source;
+pub mod store;
+
+/// A confirmed transaction's logs, as fetched from RPC or recorded in a fixture
+#[derive(Clone, Debug, Deserialize)]
+pub struct RecordedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    /// Failed transactions are stored so checkpoints can move past them, but their events are not
+    #[serde(default)]
+    pub failed: bool,
+    pub logs: Vec<String>,
+}
+
+/// Parse a JSON array of recorded transactions. Fixtures may write program IDs
+/// as `{marketplace_program}` and `{scid_program}` so they replay against any
+/// cluster's deployment.
+pub fn parse_fixture(json: &str) -> serde_json::Result<Vec<RecordedTransaction>> {
+    let json = json
+        .replace("{marketplace_program}", &MARKETPLACE_PROGRAM_ID.to_string())
+        .replace("{scid_program}", &SCID_PROGRAM_ID.to_string());
+    serde_json::from_str(&json)
+}
@@ -0,0 +1,137 @@
+//! Indexes SoundChain program events into SQLite
+//!
+//! `sync` indexes everything newer than the last checkpoint (optionally
+//! following the chain), `backfill` walks history older than the oldest
+//! checkpoint, and `import` replays recorded fixtures offline.
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand};
+use soundchain_indexer::source::RpcSource;
+use soundchain_indexer::store::Store;
+use soundchain_indexer::parse_fixture;
+use soundchain_sdk::{Pubkey, MARKETPLACE_PROGRAM_ID, SCID_PROGRAM_ID};
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+const PROGRAMS: [Pubkey; 2] = [MARKETPLACE_PROGRAM_ID, SCID_PROGRAM_ID];
+
+#[derive(Parser)]
+#[command(name = "soundchain-indexer", about = "Index SoundChain program events into SQLite")]
+struct Cli {
+    /// SQLite database path
+    #[arg(long, global = true, default_value = "soundchain-index.sqlite")]
+    db: PathBuf,
+    /// RPC endpoint
+    #[arg(long, global = true, default_value = "http://127.0.0.1:8899")]
+    url: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Index transactions newer than the last checkpoint
+    Sync {
+        /// On a fresh database, skip history before this slot (see `backfill`)
+        #[arg(long, default_value_t = 0)]
+        since_slot: u64,
+        /// Keep polling for new transactions
+        #[arg(long)]
+        follow: bool,
+        /// Seconds between polls with --follow
+        #[arg(long, default_value_t = 10)]
+        poll_interval: u64,
+    },
+    /// Index history older than the oldest checkpoint
+    Backfill {
+        /// Stop at this slot
+        #[arg(long, default_value_t = 0)]
+        until_slot: u64,
+    },
+    /// Index recorded transactions from JSON fixture files
+    Import { files: Vec<PathBuf> },
+}
+
+fn main() {
+    if let Err(err) = run(Cli::parse()) {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let mut store = Store::open(&cli.db)?;
+
+    match cli.command {
+        Command::Sync { since_slot, follow, poll_interval } => {
+            let source = RpcSource::new(cli.url);
+            loop {
+                let indexed = sync(&source, &mut store, since_slot)?;
+                eprintln!("synced {indexed} events");
+                if !follow {
+                    break;
+                }
+                thread::sleep(Duration::from_secs(poll_interval));
+            }
+        }
+        Command::Backfill { until_slot } => {
+            let source = RpcSource::new(cli.url);
+            let indexed = backfill(&source, &mut store, until_slot)?;
+            eprintln!("backfilled {indexed} events");
+        }
+        Command::Import { files } => {
+            let mut indexed = 0;
+            for file in files {
+                for transaction in parse_fixture(&std::fs::read_to_string(&file)?)? {
+                    indexed += store.index(&transaction)?;
+                }
+            }
+            eprintln!("imported {indexed} events");
+        }
+    }
+
+    Ok(())
+}
+
+/// Index each program's new transactions oldest first, checkpointing as it goes
+fn sync(source: &RpcSource, store: &mut Store, since_slot: u64) -> Result<usize> {
+    let mut indexed = 0;
+    for program in &PROGRAMS {
+        let checkpoint = store.checkpoint(program)?;
+        let until = checkpoint.newest.as_ref().map(|(signature, _)| signature.as_str());
+        for info in source.newer_signatures(program, until, since_slot)? {
+            let transaction = source.transaction(&info)?;
+            indexed += store.index(&transaction)?;
+            store.set_newest(program, &transaction.signature, transaction.slot)?;
+        }
+    }
+    Ok(indexed)
+}
+
+/// Index each program's history newest first from its oldest checkpoint back to `until_slot`
+fn backfill(source: &RpcSource, store: &mut Store, until_slot: u64) -> Result<usize> {
+    let mut indexed = 0;
+    for program in &PROGRAMS {
+        'pages: loop {
+            let checkpoint = store.checkpoint(program)?;
+            let before = checkpoint.oldest.as_ref().map(|(signature, _)| signature.as_str());
+            let page = source.older_signatures(program, before)?;
+            if page.is_empty() {
+                break;
+            }
+            for info in page {
+                if info.slot < until_slot {
+                    break 'pages;
+                }
+                let transaction = source.transaction(&info)?;
+                indexed += store.index(&transaction)?;
+                store.set_oldest(program, &transaction.signature, transaction.slot)?;
+            }
+        }
+    }
+    Ok(indexed)
+}
@@ -0,0 +1,121 @@
+//! Transaction history from RPC
+
+use std::error::Error;
+use std::str::FromStr;
+
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::UiTransactionEncoding;
+use soundchain_sdk::Pubkey;
+
+use crate::RecordedTransaction;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// Max signatures per `getSignaturesForAddress` page
+const PAGE_SIZE: usize = 1000;
+
+/// A program's signature, with the slot it landed in
+pub type SignatureInfo = RpcConfirmedTransactionStatusWithSignature;
+
+pub struct RpcSource {
+    client: RpcClient,
+}
+
+impl RpcSource {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: RpcClient::new_with_commitment(url, CommitmentConfig::finalized()),
+        }
+    }
+
+    /// Signatures newer than `until`, oldest first. With no `until`, walks back
+    /// to `since_slot`.
+    pub fn newer_signatures(
+        &self,
+        program: &Pubkey,
+        until: Option<&str>,
+        since_slot: u64,
+    ) -> Result<Vec<SignatureInfo>> {
+        let until = until.map(parse_signature).transpose()?;
+        let mut signatures = Vec::new();
+        let mut before = None;
+        loop {
+            let page = self.page(program, before, until)?;
+            let done = page.len() < PAGE_SIZE;
+            let reached_since = page.iter().any(|info| info.slot < since_slot);
+            before = page.last().map(|info| parse_signature(&info.signature)).transpose()?;
+            signatures.extend(page.into_iter().filter(|info| info.slot >= since_slot));
+            if done || reached_since {
+                break;
+            }
+        }
+        signatures.reverse();
+        Ok(signatures)
+    }
+
+    /// Up to one page of signatures older than `before`, newest first
+    pub fn older_signatures(&self, program: &Pubkey, before: Option<&str>) -> Result<Vec<SignatureInfo>> {
+        let before = before.map(parse_signature).transpose()?;
+        self.page(program, before, None)
+    }
+
+    fn page(
+        &self,
+        program: &Pubkey,
+        before: Option<Signature>,
+        until: Option<Signature>,
+    ) -> Result<Vec<SignatureInfo>> {
+        let page = self.client.get_signatures_for_address_with_config(
+            program,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: Some(PAGE_SIZE),
+                commitment: Some(CommitmentConfig::finalized()),
+            },
+        )?;
+        Ok(page)
+    }
+
+    /// Fetch a transaction's logs; failed transactions are returned without them
+    pub fn transaction(&self, info: &SignatureInfo) -> Result<RecordedTransaction> {
+        if info.err.is_some() {
+            return Ok(RecordedTransaction {
+                signature: info.signature.clone(),
+                slot: info.slot,
+                block_time: info.block_time,
+                failed: true,
+                logs: Vec::new(),
+            });
+        }
+
+        let transaction = self.client.get_transaction_with_config(
+            &parse_signature(&info.signature)?,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Json),
+                commitment: Some(CommitmentConfig::finalized()),
+                max_supported_transaction_version: Some(0),
+            },
+        )?;
+        let logs = match transaction.transaction.meta.map(|meta| meta.log_messages) {
+            Some(OptionSerializer::Some(logs)) => logs,
+            _ => Vec::new(),
+        };
+        Ok(RecordedTransaction {
+            signature: info.signature.clone(),
+            slot: transaction.slot,
+            block_time: transaction.block_time,
+            failed: false,
+            logs,
+        })
+    }
+}
+
+fn parse_signature(signature: &str) -> Result<Signature> {
+    Ok(Signature::from_str(signature)?)
+}
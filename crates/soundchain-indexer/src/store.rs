@@ -0,0 +1,412 @@
+//! SQLite storage
+//!
+//! Events are stored append-only, one table per event type, keyed by
+//! `(signature, event_index)` so re-indexing a transaction is a no-op. Current
+//! listing and SCid state is derived by the `listings` and `scids` views,
+//! ordered by slot, so live sync and backfill can index in any order.
+
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use soundchain_sdk::{ListingType, Pubkey};
+
+use crate::events::{parse_logs, Event};
+use crate::RecordedTransaction;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS transactions (
+    signature TEXT PRIMARY KEY,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    failed INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS listing_created (
+    signature TEXT NOT NULL REFERENCES transactions (signature),
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    listing TEXT NOT NULL,
+    seller TEXT NOT NULL,
+    nft_mint TEXT NOT NULL,
+    price INTEGER NOT NULL,
+    listing_type TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS listing_sold (
+    signature TEXT NOT NULL REFERENCES transactions (signature),
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    listing TEXT NOT NULL,
+    seller TEXT NOT NULL,
+    buyer TEXT NOT NULL,
+    nft_mint TEXT NOT NULL,
+    price INTEGER NOT NULL,
+    platform_fee INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS listing_cancelled (
+    signature TEXT NOT NULL REFERENCES transactions (signature),
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    listing TEXT NOT NULL,
+    seller TEXT NOT NULL,
+    nft_mint TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS listing_expired (
+    signature TEXT NOT NULL REFERENCES transactions (signature),
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    listing TEXT NOT NULL,
+    seller TEXT NOT NULL,
+    nft_mint TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS bids (
+    signature TEXT NOT NULL REFERENCES transactions (signature),
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    listing TEXT NOT NULL,
+    bidder TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    bid_count INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS scid_registered (
+    signature TEXT NOT NULL REFERENCES transactions (signature),
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    scid TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    nft_mint TEXT NOT NULL,
+    token_id INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS scid_transferred (
+    signature TEXT NOT NULL REFERENCES transactions (signature),
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    scid TEXT NOT NULL,
+    from_owner TEXT NOT NULL,
+    to_owner TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS scid_verified (
+    signature TEXT NOT NULL REFERENCES transactions (signature),
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    scid TEXT NOT NULL,
+    source_chain INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS scid_revoked (
+    signature TEXT NOT NULL REFERENCES transactions (signature),
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    scid TEXT NOT NULL,
+    revoked_by TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS listing_created_listing ON listing_created (listing, slot);
+CREATE INDEX IF NOT EXISTS listing_sold_listing ON listing_sold (listing, slot);
+CREATE INDEX IF NOT EXISTS listing_cancelled_listing ON listing_cancelled (listing, slot);
+CREATE INDEX IF NOT EXISTS listing_expired_listing ON listing_expired (listing, slot);
+CREATE INDEX IF NOT EXISTS bids_listing ON bids (listing, slot);
+CREATE INDEX IF NOT EXISTS scid_registered_scid ON scid_registered (scid);
+CREATE INDEX IF NOT EXISTS scid_transferred_scid ON scid_transferred (scid, slot);
+CREATE INDEX IF NOT EXISTS scid_verified_scid ON scid_verified (scid, slot);
+CREATE INDEX IF NOT EXISTS scid_revoked_scid ON scid_revoked (scid, slot);
+
+CREATE TABLE IF NOT EXISTS checkpoints (
+    program TEXT PRIMARY KEY,
+    newest_signature TEXT,
+    newest_slot INTEGER,
+    oldest_signature TEXT,
+    oldest_slot INTEGER
+);
+
+-- Latest lifecycle of each listing
+CREATE VIEW IF NOT EXISTS listings AS
+SELECT
+    c.listing,
+    c.seller,
+    c.nft_mint,
+    c.listing_type,
+    c.price,
+    c.timestamp AS created_at,
+    s.buyer,
+    s.price AS sold_price,
+    s.platform_fee,
+    s.timestamp AS sold_at,
+    x.timestamp AS cancelled_at,
+    e.timestamp AS expired_at,
+    (SELECT MAX(b.amount) FROM bids b WHERE b.listing = c.listing AND b.slot >= c.slot) AS highest_bid,
+    CASE
+        WHEN s.listing IS NOT NULL THEN 'sold'
+        WHEN x.listing IS NOT NULL THEN 'cancelled'
+        WHEN e.listing IS NOT NULL THEN 'expired'
+        ELSE 'active'
+    END AS status
+FROM listing_created c
+LEFT JOIN listing_sold s ON s.listing = c.listing AND s.slot >= c.slot
+LEFT JOIN listing_cancelled x ON x.listing = c.listing AND x.slot >= c.slot
+LEFT JOIN listing_expired e ON e.listing = c.listing AND e.slot >= c.slot
+WHERE c.slot = (SELECT MAX(slot) FROM listing_created WHERE listing = c.listing);
+
+-- Current owner and status of each SCid
+CREATE VIEW IF NOT EXISTS scids AS
+SELECT
+    r.scid,
+    r.nft_mint,
+    r.token_id,
+    r.timestamp AS registered_at,
+    COALESCE(
+        (SELECT t.to_owner FROM scid_transferred t WHERE t.scid = r.scid
+            ORDER BY t.slot DESC, t.event_index DESC LIMIT 1),
+        r.owner
+    ) AS owner,
+    (SELECT v.source_chain FROM scid_verified v WHERE v.scid = r.scid
+        ORDER BY v.slot DESC, v.event_index DESC LIMIT 1) AS source_chain,
+    (SELECT x.timestamp FROM scid_revoked x WHERE x.scid = r.scid
+        ORDER BY x.slot DESC LIMIT 1) AS revoked_at,
+    (SELECT x.revoked_by FROM scid_revoked x WHERE x.scid = r.scid
+        ORDER BY x.slot DESC LIMIT 1) AS revoked_by
+FROM scid_registered r;
+";
+
+/// Indexed range of a program's history
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// Newest indexed transaction; live sync resumes after it
+    pub newest: Option<(String, u64)>,
+    /// Oldest indexed transaction; backfill resumes before it
+    pub oldest: Option<(String, u64)>,
+}
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Underlying connection, for queries
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Store a transaction and its events; returns the number of events
+    /// written, which is 0 if the transaction was already indexed
+    pub fn index(&mut self, transaction: &RecordedTransaction) -> Result<usize> {
+        let db = self.conn.transaction()?;
+        let inserted = db.execute(
+            "INSERT OR IGNORE INTO transactions (signature, slot, block_time, failed) VALUES (?1, ?2, ?3, ?4)",
+            params![transaction.signature, transaction.slot, transaction.block_time, transaction.failed],
+        )?;
+        if inserted == 0 || transaction.failed {
+            db.commit()?;
+            return Ok(0);
+        }
+
+        let events = parse_logs(&transaction.logs);
+        let signature = &transaction.signature;
+        let slot = transaction.slot;
+        for (index, event) in events.iter().enumerate() {
+            match event {
+                Event::ListingCreated(e) => db.execute(
+                    "INSERT INTO listing_created
+                        (signature, event_index, slot, listing, seller, nft_mint, price, listing_type, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                    params![
+                        signature,
+                        index,
+                        slot,
+                        key(&e.listing),
+                        key(&e.seller),
+                        key(&e.nft_mint),
+                        e.price,
+                        listing_type(e.listing_type),
+                        e.timestamp
+                    ],
+                )?,
+                Event::ListingSold(e) => db.execute(
+                    "INSERT INTO listing_sold
+                        (signature, event_index, slot, listing, seller, buyer, nft_mint, price, platform_fee, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    params![
+                        signature,
+                        index,
+                        slot,
+                        key(&e.listing),
+                        key(&e.seller),
+                        key(&e.buyer),
+                        key(&e.nft_mint),
+                        e.price,
+                        e.platform_fee,
+                        e.timestamp
+                    ],
+                )?,
+                Event::BidPlaced(e) => db.execute(
+                    "INSERT INTO bids (signature, event_index, slot, listing, bidder, amount, bid_count, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        signature,
+                        index,
+                        slot,
+                        key(&e.listing),
+                        key(&e.bidder),
+                        e.amount,
+                        e.bid_count,
+                        e.timestamp
+                    ],
+                )?,
+                Event::ListingCancelled(e) => db.execute(
+                    "INSERT INTO listing_cancelled (signature, event_index, slot, listing, seller, nft_mint, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        signature,
+                        index,
+                        slot,
+                        key(&e.listing),
+                        key(&e.seller),
+                        key(&e.nft_mint),
+                        e.timestamp
+                    ],
+                )?,
+                Event::ListingExpired(e) => db.execute(
+                    "INSERT INTO listing_expired (signature, event_index, slot, listing, seller, nft_mint, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        signature,
+                        index,
+                        slot,
+                        key(&e.listing),
+                        key(&e.seller),
+                        key(&e.nft_mint),
+                        e.timestamp
+                    ],
+                )?,
+                Event::ScidRegistered(e) => db.execute(
+                    "INSERT INTO scid_registered (signature, event_index, slot, scid, owner, nft_mint, token_id, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        signature,
+                        index,
+                        slot,
+                        e.scid,
+                        key(&e.owner),
+                        key(&e.nft_mint),
+                        e.token_id,
+                        e.timestamp
+                    ],
+                )?,
+                Event::ScidTransferred(e) => db.execute(
+                    "INSERT INTO scid_transferred (signature, event_index, slot, scid, from_owner, to_owner, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![signature, index, slot, e.scid, key(&e.from), key(&e.to), e.timestamp],
+                )?,
+                Event::CrossChainVerified(e) => db.execute(
+                    "INSERT INTO scid_verified (signature, event_index, slot, scid, source_chain, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![signature, index, slot, e.scid, e.source_chain, e.timestamp],
+                )?,
+                Event::ScidRevoked(e) => db.execute(
+                    "INSERT INTO scid_revoked (signature, event_index, slot, scid, revoked_by, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![signature, index, slot, e.scid, key(&e.by), e.timestamp],
+                )?,
+            };
+        }
+
+        db.commit()?;
+        Ok(events.len())
+    }
+
+    pub fn checkpoint(&self, program: &Pubkey) -> Result<Checkpoint> {
+        let checkpoint = self
+            .conn
+            .query_row(
+                "SELECT newest_signature, newest_slot, oldest_signature, oldest_slot
+                 FROM checkpoints WHERE program = ?1",
+                [key(program)],
+                |row| {
+                    Ok(Checkpoint {
+                        newest: row.get::<_, Option<String>>(0)?.zip(row.get::<_, Option<u64>>(1)?),
+                        oldest: row.get::<_, Option<String>>(2)?.zip(row.get::<_, Option<u64>>(3)?),
+                    })
+                },
+            )
+            .optional()?;
+        Ok(checkpoint.unwrap_or_default())
+    }
+
+    /// Record `signature` as the newest indexed transaction, and as the oldest
+    /// if nothing was indexed before
+    pub fn set_newest(&self, program: &Pubkey, signature: &str, slot: u64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO checkpoints (program, newest_signature, newest_slot, oldest_signature, oldest_slot)
+             VALUES (?1, ?2, ?3, ?2, ?3)
+             ON CONFLICT (program) DO UPDATE SET
+                newest_signature = excluded.newest_signature,
+                newest_slot = excluded.newest_slot,
+                oldest_signature = COALESCE(oldest_signature, excluded.oldest_signature),
+                oldest_slot = COALESCE(oldest_slot, excluded.oldest_slot)",
+            params![key(program), signature, slot],
+        )?;
+        Ok(())
+    }
+
+    /// Record `signature` as the oldest indexed transaction
+    pub fn set_oldest(&self, program: &Pubkey, signature: &str, slot: u64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO checkpoints (program, newest_signature, newest_slot, oldest_signature, oldest_slot)
+             VALUES (?1, ?2, ?3, ?2, ?3)
+             ON CONFLICT (program) DO UPDATE SET
+                oldest_signature = excluded.oldest_signature,
+                oldest_slot = excluded.oldest_slot",
+            params![key(program), signature, slot],
+        )?;
+        Ok(())
+    }
+}
+
+fn key(pubkey: &Pubkey) -> String {
+    pubkey.to_string()
+}
+
+fn listing_type(listing_type: ListingType) -> &'static str {
+    match listing_type {
+        ListingType::FixedPrice => "fixed_price",
+        ListingType::Auction => "auction",
+        ListingType::MakeOffer => "make_offer",
+    }
+}
@@ -0,0 +1,118 @@
+//! Replays recorded transaction logs into an in-memory database
+
+use rusqlite::OptionalExtension;
+use soundchain_indexer::store::{Checkpoint, Store};
+use soundchain_indexer::{parse_fixture, RecordedTransaction};
+use soundchain_sdk::{Pubkey, MARKETPLACE_PROGRAM_ID};
+
+const MARKETPLACE_FIXTURE: &str = include_str!("fixtures/marketplace.json");
+const SCID_FIXTURE: &str = include_str!("fixtures/scid.json");
+
+/// Fixture accounts are `[n; 32]`
+fn key(n: u8) -> String {
+    Pubkey::new_from_array([n; 32]).to_string()
+}
+
+fn index_all(store: &mut Store, transactions: &[RecordedTransaction]) -> usize {
+    transactions.iter().map(|transaction| store.index(transaction).unwrap()).sum()
+}
+
+fn count(store: &Store, table: &str) -> i64 {
+    store
+        .connection()
+        .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| row.get(0))
+        .unwrap()
+}
+
+#[test]
+fn indexes_marketplace_history() {
+    let mut store = Store::open_in_memory().unwrap();
+    let transactions = parse_fixture(MARKETPLACE_FIXTURE).unwrap();
+
+    // Created, two bids and the sale of an auction, then a listing created and cancelled
+    assert_eq!(index_all(&mut store, &transactions), 6);
+    assert_eq!(count(&store, "transactions"), 8);
+    assert_eq!(count(&store, "bids"), 2);
+
+    let (status, buyer, sold_price, platform_fee, highest_bid): (String, String, u64, u64, u64) = store
+        .connection()
+        .query_row(
+            "SELECT status, buyer, sold_price, platform_fee, highest_bid FROM listings WHERE listing = ?1",
+            [key(1)],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .unwrap();
+    assert_eq!(status, "sold");
+    assert_eq!(buyer, key(5));
+    assert_eq!(sold_price, 1_200_000);
+    assert_eq!(platform_fee, 30_000);
+    assert_eq!(highest_bid, 1_200_000);
+
+    // The failed buy of the second listing is not a sale
+    let status: String = store
+        .connection()
+        .query_row("SELECT status FROM listings WHERE listing = ?1", [key(6)], |row| row.get(0))
+        .unwrap();
+    assert_eq!(status, "cancelled");
+    assert_eq!(count(&store, "listing_sold"), 1);
+}
+
+#[test]
+fn ignores_events_logged_by_other_programs() {
+    let mut store = Store::open_in_memory().unwrap();
+    index_all(&mut store, &parse_fixture(MARKETPLACE_FIXTURE).unwrap());
+
+    let forged: Option<String> = store
+        .connection()
+        .query_row("SELECT listing FROM listing_sold WHERE listing = ?1", [key(8)], |row| row.get(0))
+        .optional()
+        .unwrap();
+    assert_eq!(forged, None);
+}
+
+#[test]
+fn reindexing_is_a_no_op() {
+    let mut store = Store::open_in_memory().unwrap();
+    let transactions = parse_fixture(MARKETPLACE_FIXTURE).unwrap();
+
+    index_all(&mut store, &transactions);
+    assert_eq!(index_all(&mut store, &transactions), 0);
+    assert_eq!(count(&store, "bids"), 2);
+    assert_eq!(count(&store, "listing_created"), 2);
+}
+
+#[test]
+fn backfill_order_gives_the_same_state() {
+    let mut store = Store::open_in_memory().unwrap();
+    let mut transactions = parse_fixture(SCID_FIXTURE).unwrap();
+    transactions.reverse();
+
+    assert_eq!(index_all(&mut store, &transactions), 4);
+
+    let (owner, source_chain, revoked_by): (String, u16, String) = store
+        .connection()
+        .query_row(
+            "SELECT owner, source_chain, revoked_by FROM scids WHERE scid = 'SC-SOL-7B3A-2500001'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap();
+    assert_eq!(owner, key(12));
+    assert_eq!(source_chain, 7000);
+    assert_eq!(revoked_by, key(12));
+}
+
+#[test]
+fn checkpoints_track_both_ends() {
+    let store = Store::open_in_memory().unwrap();
+    let program = MARKETPLACE_PROGRAM_ID;
+    assert_eq!(store.checkpoint(&program).unwrap(), Checkpoint::default());
+
+    store.set_newest(&program, "sig-200", 200).unwrap();
+    store.set_newest(&program, "sig-300", 300).unwrap();
+    store.set_oldest(&program, "sig-100", 100).unwrap();
+
+    let checkpoint = store.checkpoint(&program).unwrap();
+    assert_eq!(checkpoint.newest, Some(("sig-300".to_string(), 300)));
+    assert_eq!(checkpoint.oldest, Some(("sig-100".to_string(), 100)));
+}
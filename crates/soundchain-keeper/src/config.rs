@@ -0,0 +1,77 @@
+//! Keeper configuration file
+
+use std::error::Error;
+use std::path::Path;
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// RPC endpoint
+    #[serde(default = "default_url")]
+    pub url: String,
+    /// Keypair that signs and pays for crank transactions
+    #[serde(default = "default_keypair")]
+    pub keypair: String,
+    /// Seconds between scans
+    #[serde(default = "default_poll_interval")]
+    pub poll_interval: u64,
+    /// Send `settle_auction` for ended auctions
+    #[serde(default = "enabled")]
+    pub settle_auctions: bool,
+    /// Send `expire_listing` for expired fixed-price listings
+    #[serde(default = "enabled")]
+    pub expire_listings: bool,
+    /// Attempts per listing before giving up until the next scan
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled after each failure
+    #[serde(default = "default_backoff_ms")]
+    pub backoff_ms: u64,
+    /// Upper bound on the retry delay
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    /// Address to serve Prometheus metrics on, e.g. `127.0.0.1:9464`
+    pub metrics_addr: Option<String>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| format!("reading config {}: {err}", path.display()))?;
+        let config: Self = toml::from_str(&contents)?;
+        if config.max_attempts == 0 {
+            return Err("max_attempts must be at least 1".into());
+        }
+        Ok(config)
+    }
+}
+
+fn default_url() -> String {
+    "http://127.0.0.1:8899".to_string()
+}
+
+fn default_keypair() -> String {
+    "~/.config/solana/id.json".to_string()
+}
+
+fn default_poll_interval() -> u64 {
+    30
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn default_max_attempts() -> u32 {
+    5
+}
+
+fn default_backoff_ms() -> u64 {
+    500
+}
+
+fn default_max_backoff_ms() -> u64 {
+    30_000
+}
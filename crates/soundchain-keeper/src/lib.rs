@@ -0,0 +1,12 @@
+//! SoundChain keeper
+//!
+//! Scanning, configuration and metrics for the `soundchain-keeper` binary,
+//! which settles ended auctions and expires stale fixed-price listings.
+
+use std::error::Error;
+
+pub mod config;
+pub mod metrics;
+pub mod scan;
+
+pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
@@ -0,0 +1,217 @@
+//! SoundChain keeper
+//!
+//! Auctions only pay out when someone sends `settle_auction`, and expired
+//! fixed-price listings keep their NFT in escrow until `expire_listing` runs.
+//! The keeper scans for active listings past their end time and sends those
+//! cranks, retrying with exponential backoff. Token accounts the cranks need
+//! are created idempotently first, with the keeper paying their rent.
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use anchor_spl::token;
+use clap::Parser;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+use soundchain_keeper::config::Config;
+use soundchain_keeper::metrics::{self, Metrics};
+use soundchain_keeper::scan::{self, DueListing};
+use soundchain_keeper::Result;
+use soundchain_sdk::marketplace::{self, SettleOptions};
+use soundchain_sdk::{pda, Auction, Instruction, Listing, ListingType, Marketplace, Pubkey};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+#[derive(Parser)]
+#[command(name = "soundchain-keeper", about = "Settle ended SoundChain auctions and expire stale listings")]
+struct Cli {
+    /// Keeper configuration file
+    #[arg(long, default_value = "keeper.toml")]
+    config: PathBuf,
+    /// Scan once and exit
+    #[arg(long)]
+    once: bool,
+    /// Print due listings without sending anything
+    #[arg(long)]
+    dry_run: bool,
+}
+
+fn main() {
+    if let Err(err) = run(Cli::parse()) {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let config = Config::load(&cli.config)?;
+    let keypair = keypair(&config.keypair)?;
+    let client = RpcClient::new_with_commitment(config.url.clone(), CommitmentConfig::confirmed());
+    let metrics = Arc::new(Metrics::default());
+    if let Some(addr) = &config.metrics_addr {
+        metrics::serve(addr, metrics.clone())?;
+    }
+
+    loop {
+        if let Err(err) = scan(&cli, &config, &client, &keypair, &metrics) {
+            Metrics::inc(&metrics.scan_errors);
+            eprintln!("scan failed: {err}");
+            if cli.once {
+                return Err(err);
+            }
+        }
+        if cli.once {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(config.poll_interval));
+    }
+}
+
+fn scan(cli: &Cli, config: &Config, client: &RpcClient, keypair: &Keypair, metrics: &Metrics) -> Result<()> {
+    let marketplace =
+        soundchain_sdk::decode_marketplace(&client.get_account_data(&pda::find_marketplace_address().0)?)?;
+    let scan::Scan { now, due, skipped } = scan::due_listings(client)?;
+    for skipped in &skipped {
+        eprintln!("skipping {}: {}", skipped.address, skipped.reason);
+    }
+    let due: Vec<_> = due
+        .into_iter()
+        .filter(|due| match due.listing.listing_type {
+            ListingType::Auction => config.settle_auctions,
+            _ => config.expire_listings,
+        })
+        .collect();
+
+    Metrics::inc(&metrics.scans);
+    metrics.last_scan_timestamp.store(now, Ordering::Relaxed);
+    metrics.due_listings.store(due.len() as u64, Ordering::Relaxed);
+    metrics.skipped.fetch_add(skipped.len() as u64, Ordering::Relaxed);
+
+    for due in &due {
+        let action = match due.auction {
+            Some(_) => "settle",
+            None => "expire",
+        };
+        if cli.dry_run {
+            println!("{action} {}", due.address);
+            continue;
+        }
+
+        let instructions = crank_instructions(&keypair.pubkey(), &marketplace, due);
+        match submit(config, client, keypair, metrics, &due.address, &instructions) {
+            Ok(Some(signature)) => {
+                Metrics::inc(match due.auction {
+                    Some(_) => &metrics.settled,
+                    None => &metrics.expired,
+                });
+                println!("{action} {} {signature}", due.address);
+            }
+            Ok(None) => {
+                Metrics::inc(&metrics.already_closed);
+                println!("{action} {} already closed", due.address);
+            }
+            Err(err) => {
+                Metrics::inc(&metrics.failures);
+                eprintln!("{action} {} failed: {err}", due.address);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Token account creation for every account the crank requires, then the crank
+fn crank_instructions(payer: &Pubkey, marketplace: &Marketplace, due: &DueListing) -> Vec<Instruction> {
+    let listing = &due.listing;
+    let mut token_accounts = vec![(listing.seller, listing.nft_mint)];
+
+    let crank = match &due.auction {
+        Some(auction) => {
+            token_accounts.extend(settle_token_accounts(marketplace, listing, auction, &due.address));
+            marketplace::settle_auction(payer, marketplace, listing, auction, &SettleOptions::default())
+        }
+        None => marketplace::expire_listing(listing),
+    };
+
+    let mut instructions: Vec<Instruction> = token_accounts
+        .iter()
+        .map(|(owner, mint)| create_associated_token_account_idempotent(payer, owner, mint, &token::ID))
+        .collect();
+    instructions.push(crank);
+    instructions
+}
+
+/// `(owner, mint)` of the token accounts `settle_auction` uses besides the seller's NFT account
+fn settle_token_accounts(
+    marketplace: &Marketplace,
+    listing: &Listing,
+    auction: &Auction,
+    listing_address: &Pubkey,
+) -> Vec<(Pubkey, Pubkey)> {
+    let auction_address = pda::find_auction_address(listing_address).0;
+    let mut accounts = vec![
+        (listing.seller, listing.payment_mint),
+        (marketplace.fee_collector, listing.payment_mint),
+        (auction_address, listing.payment_mint),
+    ];
+    if auction.reserve_met {
+        accounts.push((auction.current_bidder, listing.nft_mint));
+        if marketplace.trading_reward(listing, auction.current_bid) > 0 {
+            accounts.push((auction.current_bidder, marketplace.rewards_mint));
+        }
+        if let Some(referrer) = auction.referrer {
+            accounts.push((referrer, listing.payment_mint));
+        }
+    }
+    accounts
+}
+
+/// Send the crank, retrying with exponential backoff. Returns `None` if the
+/// listing was closed by someone else in the meantime.
+fn submit(
+    config: &Config,
+    client: &RpcClient,
+    keypair: &Keypair,
+    metrics: &Metrics,
+    listing: &Pubkey,
+    instructions: &[Instruction],
+) -> Result<Option<String>> {
+    let mut delay = config.backoff_ms;
+    for attempt in 1..=config.max_attempts {
+        let err: Box<dyn Error> = match send(client, keypair, instructions) {
+            Ok(signature) => return Ok(Some(signature)),
+            Err(err) => err,
+        };
+        if !scan::is_active(client, listing).unwrap_or(true) {
+            return Ok(None);
+        }
+        if attempt == config.max_attempts {
+            return Err(err);
+        }
+
+        eprintln!("attempt {attempt} for {listing} failed, retrying in {delay}ms: {err}");
+        Metrics::inc(&metrics.retries);
+        thread::sleep(Duration::from_millis(delay));
+        delay = (delay * 2).min(config.max_backoff_ms);
+    }
+    unreachable!("max_attempts is at least 1")
+}
+
+fn send(client: &RpcClient, keypair: &Keypair, instructions: &[Instruction]) -> Result<String> {
+    let blockhash = client.get_latest_blockhash()?;
+    let transaction =
+        Transaction::new_signed_with_payer(instructions, Some(&keypair.pubkey()), &[keypair], blockhash);
+    Ok(client.send_and_confirm_transaction(&transaction)?.to_string())
+}
+
+fn keypair(path: &str) -> Result<Keypair> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => format!("{}/{rest}", std::env::var("HOME")?),
+        None => path.to_string(),
+    };
+    read_keypair_file(&path).map_err(|err| format!("reading keypair {path}: {err}").into())
+}
@@ -0,0 +1,87 @@
+//! Prometheus metrics
+//!
+//! Counters live in atomics shared with the crank loop. With `metrics_addr`
+//! set, a background thread answers every HTTP request with the text
+//! exposition format.
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+#[derive(Default)]
+pub struct Metrics {
+    pub scans: AtomicU64,
+    pub scan_errors: AtomicU64,
+    pub last_scan_timestamp: AtomicI64,
+    pub due_listings: AtomicU64,
+    pub skipped: AtomicU64,
+    pub settled: AtomicU64,
+    pub expired: AtomicU64,
+    pub already_closed: AtomicU64,
+    pub retries: AtomicU64,
+    pub failures: AtomicU64,
+}
+
+impl Metrics {
+    pub fn inc(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn render(&self) -> String {
+        let get = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        let metrics: [(&str, &str, &str, String); 10] = [
+            ("scans_total", "counter", "Completed scans", get(&self.scans).to_string()),
+            ("scan_errors_total", "counter", "Scans that failed to load accounts", get(&self.scan_errors).to_string()),
+            (
+                "last_scan_timestamp",
+                "gauge",
+                "Cluster time of the last completed scan",
+                self.last_scan_timestamp.load(Ordering::Relaxed).to_string(),
+            ),
+            ("due_listings", "gauge", "Listings due for a crank in the last scan", get(&self.due_listings).to_string()),
+            ("skipped_total", "counter", "Listings and auctions that failed to load", get(&self.skipped).to_string()),
+            ("settled_total", "counter", "Auctions settled", get(&self.settled).to_string()),
+            ("expired_total", "counter", "Fixed-price listings expired", get(&self.expired).to_string()),
+            (
+                "already_closed_total",
+                "counter",
+                "Listings closed by someone else before our crank landed",
+                get(&self.already_closed).to_string(),
+            ),
+            ("retries_total", "counter", "Crank transactions retried", get(&self.retries).to_string()),
+            ("failures_total", "counter", "Listings given up on after max_attempts", get(&self.failures).to_string()),
+        ];
+
+        let mut out = String::new();
+        for (name, kind, help, value) in metrics {
+            out.push_str(&format!(
+                "# HELP soundchain_keeper_{name} {help}\n# TYPE soundchain_keeper_{name} {kind}\nsoundchain_keeper_{name} {value}\n"
+            ));
+        }
+        out
+    }
+}
+
+/// Serve `metrics` on `addr` from a background thread
+pub fn serve(addr: &str, metrics: Arc<Metrics>) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else {
+                continue;
+            };
+            // The request itself is ignored; every path returns the metrics
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request);
+            let body = metrics.render();
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,115 @@
+//! Finding listings that are due for a crank
+
+use anchor_lang::Discriminator;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::account::from_account;
+use solana_sdk::clock::Clock;
+use solana_sdk::sysvar;
+use soundchain_sdk::{pda, Auction, Listing, ListingStatus, ListingType, Pubkey, MARKETPLACE_PROGRAM_ID};
+
+use crate::Result;
+
+/// Offset of `Listing::status`: discriminator, seller, nft_mint, payment_mint,
+/// price and listing_type come first
+const LISTING_STATUS_OFFSET: usize = 8 + 32 * 3 + 8 + 1;
+
+/// A listing past its end time, and the auction for auction listings
+pub struct DueListing {
+    pub address: Pubkey,
+    pub listing: Listing,
+    pub auction: Option<Auction>,
+}
+
+/// An account the scan couldn't load, and why
+pub struct Skipped {
+    pub address: Pubkey,
+    pub reason: String,
+}
+
+/// Result of one scan at cluster time `now`
+pub struct Scan {
+    pub now: i64,
+    pub due: Vec<DueListing>,
+    pub skipped: Vec<Skipped>,
+}
+
+/// Active listings whose `expires_at` has passed on the cluster clock.
+/// Listings that don't decode and auctions whose auction account can't be
+/// loaded are reported in `skipped`.
+pub fn due_listings(client: &RpcClient) -> Result<Scan> {
+    let now = cluster_time(client)?;
+    let mut due = Vec::new();
+    let mut skipped = Vec::new();
+    for (address, listing) in active_listings(client, &mut skipped)? {
+        if !is_due(&listing, now) {
+            continue;
+        }
+        let auction = match listing.listing_type {
+            ListingType::Auction => match auction(client, &address) {
+                Ok(auction) => Some(auction),
+                Err(err) => {
+                    skipped.push(Skipped { address, reason: format!("auction: {err}") });
+                    continue;
+                }
+            },
+            _ => None,
+        };
+        due.push(DueListing { address, listing, auction });
+    }
+    Ok(Scan { now, due, skipped })
+}
+
+/// Whether a listing needs a crank at cluster time `now`: an active auction or
+/// fixed-price listing that has reached `expires_at`
+pub fn is_due(listing: &Listing, now: i64) -> bool {
+    listing.status == ListingStatus::Active
+        && listing.listing_type != ListingType::MakeOffer
+        && listing.expires_at <= now
+}
+
+/// Whether the listing is still open; false once anyone has settled,
+/// expired or cancelled it
+pub fn is_active(client: &RpcClient, address: &Pubkey) -> Result<bool> {
+    let listing = soundchain_sdk::decode_listing(&client.get_account_data(address)?)?;
+    Ok(listing.status == ListingStatus::Active)
+}
+
+fn active_listings(client: &RpcClient, skipped: &mut Vec<Skipped>) -> Result<Vec<(Pubkey, Listing)>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, Listing::DISCRIMINATOR.to_vec())),
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                LISTING_STATUS_OFFSET,
+                vec![ListingStatus::Active as u8],
+            )),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        with_context: None,
+    };
+
+    let mut listings = Vec::new();
+    for (address, account) in client.get_program_accounts_with_config(&MARKETPLACE_PROGRAM_ID, config)? {
+        match soundchain_sdk::decode_listing(&account.data) {
+            Ok(listing) => listings.push((address, listing)),
+            Err(err) => skipped.push(Skipped { address, reason: format!("listing: {err}") }),
+        }
+    }
+    Ok(listings)
+}
+
+fn auction(client: &RpcClient, listing: &Pubkey) -> Result<Auction> {
+    let address = pda::find_auction_address(listing).0;
+    Ok(soundchain_sdk::decode_auction(&client.get_account_data(&address)?)?)
+}
+
+fn cluster_time(client: &RpcClient) -> Result<i64> {
+    let account = client.get_account(&sysvar::clock::ID)?;
+    let clock: Clock = from_account(&account).ok_or("decoding clock sysvar")?;
+    Ok(clock.unix_timestamp)
+}
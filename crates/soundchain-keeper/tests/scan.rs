@@ -0,0 +1,44 @@
+//! Which listings the keeper cranks
+
+use soundchain_keeper::scan::is_due;
+use soundchain_sdk::{Listing, ListingStatus, ListingType, Pubkey};
+
+const NOW: i64 = 1_700_000_000;
+
+fn listing(listing_type: ListingType, status: ListingStatus, expires_at: i64) -> Listing {
+    Listing {
+        seller: Pubkey::new_unique(),
+        nft_mint: Pubkey::new_unique(),
+        payment_mint: Pubkey::new_unique(),
+        price: 1_000_000,
+        listing_type,
+        status,
+        created_at: NOW - 86_400,
+        expires_at,
+        scid: None,
+        buyer: None,
+        sold_at: None,
+        version: 4,
+        usd_price: None,
+        restriction: None,
+        token_gate: None,
+    }
+}
+
+#[test]
+fn ended_auctions_and_fixed_price_listings_are_due() {
+    for listing_type in [ListingType::Auction, ListingType::FixedPrice] {
+        assert!(is_due(&listing(listing_type, ListingStatus::Active, NOW), NOW));
+        assert!(is_due(&listing(listing_type, ListingStatus::Active, NOW - 1), NOW));
+        assert!(!is_due(&listing(listing_type, ListingStatus::Active, NOW + 1), NOW));
+    }
+}
+
+#[test]
+fn closed_listings_and_offers_are_never_due() {
+    assert!(!is_due(&listing(ListingType::MakeOffer, ListingStatus::Active, NOW - 1), NOW));
+    for status in [ListingStatus::Sold, ListingStatus::Cancelled, ListingStatus::Expired] {
+        assert!(!is_due(&listing(ListingType::Auction, status, NOW - 1), NOW));
+        assert!(!is_due(&listing(ListingType::FixedPrice, status, NOW - 1), NOW));
+    }
+}
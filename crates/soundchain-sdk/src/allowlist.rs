@@ -0,0 +1,55 @@
+//! Merkle allowlists for restricted listings
+//!
+//! `BuyerRestriction::Allowlist` stores only the root. Sellers build the tree
+//! from the wallets they want to let in and publish it; buyers pass their
+//! `proof` to `buy_with_proof` or `place_bid_with_proof`.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::keccak;
+
+pub use soundchain_marketplace::allowlist_root;
+
+/// Merkle root of `buyers`; an empty list has a root no one can prove
+pub fn root(buyers: &[Pubkey]) -> [u8; 32] {
+    levels(buyers).last().and_then(|level| level.first().copied()).unwrap_or_default()
+}
+
+/// Proof that `buyer` is in `buyers`, or `None` if it isn't
+pub fn proof(buyers: &[Pubkey], buyer: &Pubkey) -> Option<Vec<[u8; 32]>> {
+    let mut index = buyers.iter().position(|wallet| wallet == buyer)?;
+    let levels = levels(buyers);
+    let mut proof = Vec::new();
+    for level in &levels[..levels.len() - 1] {
+        // An odd node out is carried up unpaired and needs no sibling
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        index /= 2;
+    }
+    Some(proof)
+}
+
+/// Tree levels from the leaves up to the root
+fn levels(buyers: &[Pubkey]) -> Vec<Vec<[u8; 32]>> {
+    if buyers.is_empty() {
+        return Vec::new();
+    }
+    let mut levels = vec![buyers.iter().map(|buyer| keccak::hash(buyer.as_ref()).0).collect::<Vec<_>>()];
+    while levels[levels.len() - 1].len() > 1 {
+        let next = levels[levels.len() - 1]
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => hash_pair(*left, *right),
+                [node] => *node,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    keccak::hashv(&[&left, &right]).0
+}
@@ -0,0 +1,63 @@
+//! SoundChain client SDK
+//!
+//! Off-chain helpers for the SoundChain Marketplace and SCid programs: PDA
+//! derivation, instruction builders that fill in every account, account
+//! decoders and the fee and bid math the programs enforce.
+//!
+//! Account layouts and instruction arguments come straight from the program
+//! crates, so a program change that breaks a client breaks this crate's build
+//! instead of drifting silently.
+
+use anchor_lang::AccountDeserialize;
+
+pub mod allowlist;
+pub mod marketplace;
+pub mod math;
+pub mod oracle;
+pub mod pda;
+pub mod scid;
+
+pub use anchor_lang::prelude::Pubkey;
+pub use anchor_lang::solana_program::instruction::Instruction;
+pub use soundchain_marketplace::{
+    Auction, BuyerRestriction, FeeDiscount, FeeSchedule, Listing, ListingPrices, ListingStatus, ListingType, Marketplace,
+    MintPrice, PriceFeed, TokenGate, ID as MARKETPLACE_PROGRAM_ID,
+};
+pub use soundchain_scid::{Registry, ScidRecord, ID as SCID_PROGRAM_ID};
+
+// ============ Decoders ============
+
+/// Decode a `Marketplace` account, checking its discriminator
+pub fn decode_marketplace(data: &[u8]) -> anchor_lang::Result<Marketplace> {
+    Marketplace::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `Listing` account, checking its discriminator
+pub fn decode_listing(data: &[u8]) -> anchor_lang::Result<Listing> {
+    Listing::try_deserialize(&mut &data[..])
+}
+
+/// Decode an `Auction` account, checking its discriminator
+pub fn decode_auction(data: &[u8]) -> anchor_lang::Result<Auction> {
+    Auction::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `ListingPrices` account, checking its discriminator
+pub fn decode_listing_prices(data: &[u8]) -> anchor_lang::Result<ListingPrices> {
+    ListingPrices::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `PriceFeed` account, checking its discriminator
+pub fn decode_price_feed(data: &[u8]) -> anchor_lang::Result<PriceFeed> {
+    PriceFeed::try_deserialize(&mut &data[..])
+}
+
+/// Decode the SCid `Registry` account, checking its discriminator
+pub fn decode_registry(data: &[u8]) -> anchor_lang::Result<Registry> {
+    Registry::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `ScidRecord` account, checking its discriminator
+pub fn decode_scid_record(data: &[u8]) -> anchor_lang::Result<ScidRecord> {
+    ScidRecord::try_deserialize(&mut &data[..])
+}
@@ -0,0 +1,624 @@
+//! Instruction builders for the marketplace program
+//!
+//! Builders take the decoded on-chain state they depend on and derive every
+//! other account. Token accounts are the owners' associated token accounts and
+//! must exist before the instruction runs, except for accounts the program
+//! creates itself (escrow and stats).
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token;
+
+use soundchain_marketplace::{
+    accounts, instruction, Auction, BuyerRestriction, Listing, ListingType, Marketplace, MintPrice, Role, TokenGate, ID,
+};
+
+use crate::pda;
+
+/// Optional accounts for `create_listing` and `create_auction`
+#[derive(Clone, Debug, Default)]
+pub struct ListingOptions {
+    /// Verified collection of the NFT; counts the listing in its collection stats,
+    /// which must already exist (`init_collection_stats`)
+    pub collection: Option<Pubkey>,
+}
+
+/// Optional accounts for `buy`
+#[derive(Clone, Debug, Default)]
+pub struct BuyOptions {
+    /// Verified collection of the NFT, needed for `collection_fee` and `collection_stats`
+    pub collection: Option<Pubkey>,
+    /// Apply the collection's fee override
+    pub collection_fee: bool,
+    /// Apply the seller's partner fee
+    pub partner_fee: bool,
+    /// Record the sale in the collection stats
+    pub collection_stats: bool,
+    /// Buyer's OGUN token account, for the holder discount
+    pub ogun_account: Option<Pubkey>,
+    /// Buyer's stake account (`pda::find_stake_address`), for the staker discount
+    pub stake_account: Option<Pubkey>,
+    /// Approved referrer wallet to share the platform fee with
+    pub referrer: Option<Pubkey>,
+    /// NFT the buyer holds from the listing's gate collection; balance gates
+    /// need nothing here
+    pub gate_nft: Option<Pubkey>,
+}
+
+/// Optional accounts for `settle_auction`
+#[derive(Clone, Debug, Default)]
+pub struct SettleOptions {
+    /// Verified collection of the NFT; records the sale in its collection stats
+    pub collection: Option<Pubkey>,
+}
+
+/// Initialize the marketplace
+pub fn initialize(authority: &Pubkey, fee_collector: Pubkey, platform_fee: u16) -> Instruction {
+    let accounts = accounts::Initialize {
+        marketplace: pda::find_marketplace_address().0,
+        authority: *authority,
+        system_program: system_program::ID,
+    };
+    build(accounts, instruction::Initialize { fee_collector, platform_fee })
+}
+
+/// List an NFT at a fixed price
+pub fn create_listing(
+    seller: &Pubkey,
+    nft_mint: &Pubkey,
+    payment_mint: &Pubkey,
+    price: u64,
+    duration: i64,
+    scid: Option<String>,
+    options: &ListingOptions,
+) -> Instruction {
+    build(
+        create_listing_accounts(seller, nft_mint, payment_mint, options),
+        instruction::CreateListing { price, duration, scid },
+    )
+}
+
+/// List an NFT at a fixed USD price in micro-dollars. Buyers pay in any mint
+/// with a price feed (`set_price_feed`), including `payment_mint`, which needs one.
+pub fn create_usd_listing(
+    seller: &Pubkey,
+    nft_mint: &Pubkey,
+    payment_mint: &Pubkey,
+    usd_price: u64,
+    duration: i64,
+    scid: Option<String>,
+    options: &ListingOptions,
+) -> Instruction {
+    let accounts = accounts::CreateListing {
+        price_feed: Some(pda::find_price_feed_address(payment_mint).0),
+        ..create_listing_accounts(seller, nft_mint, payment_mint, options)
+    };
+    build(accounts, instruction::CreateUsdListing { usd_price, duration, scid })
+}
+
+/// List an NFT for auction
+pub fn create_auction(
+    seller: &Pubkey,
+    nft_mint: &Pubkey,
+    payment_mint: &Pubkey,
+    reserve_price: u64,
+    duration: i64,
+    scid: Option<String>,
+    options: &ListingOptions,
+) -> Instruction {
+    build(
+        create_listing_accounts(seller, nft_mint, payment_mint, options),
+        instruction::CreateAuction { reserve_price, duration, scid },
+    )
+}
+
+fn create_listing_accounts(
+    seller: &Pubkey,
+    nft_mint: &Pubkey,
+    payment_mint: &Pubkey,
+    options: &ListingOptions,
+) -> accounts::CreateListing {
+    let listing = pda::find_listing_address(nft_mint).0;
+    accounts::CreateListing {
+        marketplace: pda::find_marketplace_address().0,
+        listing,
+        nft_mint: *nft_mint,
+        payment_mint: *payment_mint,
+        seller: *seller,
+        seller_nft_account: get_associated_token_address(seller, nft_mint),
+        escrow_nft_account: get_associated_token_address(&listing, nft_mint),
+        seller_stats: pda::find_seller_stats_address(seller, payment_mint).0,
+        nft_metadata: options.collection.map(|_| pda::find_metadata_address(nft_mint).0),
+        collection_stats: options
+            .collection
+            .map(|collection| pda::find_collection_stats_address(&collection, payment_mint).0),
+        token_program: token::ID,
+        associated_token_program: associated_token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+        price_feed: None,
+    }
+}
+
+/// Buy a fixed-price listing
+pub fn buy(buyer: &Pubkey, marketplace: &Marketplace, listing: &Listing, options: &BuyOptions) -> Instruction {
+    build(buy_accounts(buyer, marketplace, listing, listing.price, options), instruction::Buy {})
+}
+
+/// Buy a fixed-price listing restricted to an allowlist, with the buyer's
+/// proof from `allowlist::proof`
+pub fn buy_with_proof(
+    buyer: &Pubkey,
+    marketplace: &Marketplace,
+    listing: &Listing,
+    proof: Vec<[u8; 32]>,
+    options: &BuyOptions,
+) -> Instruction {
+    build(
+        buy_accounts(buyer, marketplace, listing, listing.price, options),
+        instruction::BuyWithProof { proof },
+    )
+}
+
+/// Buy a fixed-price listing in one of its extra payment mints, at that mint's
+/// price from the listing's `ListingPrices`
+pub fn buy_in_mint(
+    buyer: &Pubkey,
+    marketplace: &Marketplace,
+    listing: &Listing,
+    payment_mint: &Pubkey,
+    price: u64,
+    options: &BuyOptions,
+) -> Instruction {
+    let listing_key = pda::find_listing_address(&listing.nft_mint).0;
+    let priced = Listing {
+        payment_mint: *payment_mint,
+        price,
+        ..listing.clone()
+    };
+    let accounts = accounts::Buy {
+        listing_prices: Some(pda::find_listing_prices_address(&listing_key).0),
+        ..buy_accounts(buyer, marketplace, &priced, price, options)
+    };
+    build(accounts, instruction::Buy {})
+}
+
+/// Buy a USD-priced listing, paying at most `max_amount` of `payment_mint`,
+/// any mint with a price feed. `oracle` is that mint's `PriceFeed::oracle`.
+pub fn buy_usd(
+    buyer: &Pubkey,
+    marketplace: &Marketplace,
+    listing: &Listing,
+    payment_mint: &Pubkey,
+    oracle: &Pubkey,
+    max_amount: u64,
+    options: &BuyOptions,
+) -> Instruction {
+    // The price is only known on-chain; the max decides whether rewards are due
+    let priced = Listing {
+        payment_mint: *payment_mint,
+        ..listing.clone()
+    };
+    let accounts = accounts::Buy {
+        price_feed: Some(pda::find_price_feed_address(payment_mint).0),
+        price_oracle: Some(*oracle),
+        ..buy_accounts(buyer, marketplace, &priced, max_amount, options)
+    };
+    build(accounts, instruction::BuyUsd { max_amount })
+}
+
+/// Swap program and pool for `buy_with_swap`
+#[derive(Clone, Debug)]
+pub struct SwapRoute {
+    pub program_id: Pubkey,
+    /// Mint the buyer pays in; taken from their associated token account
+    pub source_mint: Pubkey,
+    /// Pool accounts the swap program expects after the interface accounts
+    pub pool_accounts: Vec<AccountMeta>,
+}
+
+/// Buy a fixed-price listing paying `amount_in` of `route.source_mint`,
+/// swapped into the listing's payment mint with at least `min_amount_out`
+/// received. The buyer's payment mint token account must exist.
+pub fn buy_with_swap(
+    buyer: &Pubkey,
+    marketplace: &Marketplace,
+    listing: &Listing,
+    route: &SwapRoute,
+    amount_in: u64,
+    min_amount_out: u64,
+    options: &BuyOptions,
+) -> Instruction {
+    let accounts = accounts::Buy {
+        swap_program: Some(route.program_id),
+        swap_source_account: Some(get_associated_token_address(buyer, &route.source_mint)),
+        ..buy_accounts(buyer, marketplace, listing, listing.price, options)
+    };
+    let mut instruction = build(accounts, instruction::BuyWithSwap { amount_in, min_amount_out });
+    instruction.accounts.extend(route.pool_accounts.iter().cloned());
+    instruction
+}
+
+fn buy_accounts(
+    buyer: &Pubkey,
+    marketplace: &Marketplace,
+    listing: &Listing,
+    price: u64,
+    options: &BuyOptions,
+) -> accounts::Buy {
+    let listing_key = pda::find_listing_address(&listing.nft_mint).0;
+    let discounted = options.ogun_account.is_some() || options.stake_account.is_some();
+    let metadata_needed = options.collection_fee || options.collection_stats;
+    let collection = options.collection.filter(|_| metadata_needed);
+    let (gate_token_account, gate_nft_metadata) = gate_accounts(buyer, listing, options.gate_nft);
+
+    accounts::Buy {
+        marketplace: pda::find_marketplace_address().0,
+        listing: listing_key,
+        buyer: *buyer,
+        buyer_payment_account: get_associated_token_address(buyer, &listing.payment_mint),
+        buyer_nft_account: get_associated_token_address(buyer, &listing.nft_mint),
+        seller_payment_account: get_associated_token_address(&listing.seller, &listing.payment_mint),
+        fee_collector_account: get_associated_token_address(&marketplace.fee_collector, &listing.payment_mint),
+        escrow_nft_account: get_associated_token_address(&listing_key, &listing.nft_mint),
+        token_program: token::ID,
+        rewards_vault: rewards_vault(marketplace, listing, price),
+        fee_schedule: discounted.then(|| pda::find_fee_schedule_address().0),
+        collection_fee: collection
+            .filter(|_| options.collection_fee)
+            .map(|collection| pda::find_collection_fee_address(&collection).0),
+        nft_metadata: collection.map(|_| pda::find_metadata_address(&listing.nft_mint).0),
+        partner_fee: options
+            .partner_fee
+            .then(|| pda::find_partner_fee_address(&listing.seller).0),
+        buyer_ogun_account: options.ogun_account,
+        buyer_stake_account: options.stake_account,
+        referrer: options.referrer.map(|wallet| pda::find_referrer_address(&wallet).0),
+        referrer_payment_account: options
+            .referrer
+            .map(|wallet| get_associated_token_address(&wallet, &listing.payment_mint)),
+        seller_stats: pda::find_seller_stats_address(&listing.seller, &listing.payment_mint).0,
+        collection_stats: collection
+            .filter(|_| options.collection_stats)
+            .map(|collection| pda::find_collection_stats_address(&collection, &listing.payment_mint).0),
+        system_program: system_program::ID,
+        price_feed: None,
+        price_oracle: None,
+        listing_prices: None,
+        swap_program: None,
+        swap_source_account: None,
+        gate_token_account,
+        gate_nft_metadata,
+    }
+}
+
+/// Bid on an auction, refunding the previous bidder
+pub fn place_bid(
+    bidder: &Pubkey,
+    listing: &Listing,
+    auction: &Auction,
+    amount: u64,
+    referrer: Option<Pubkey>,
+    gate_nft: Option<Pubkey>,
+) -> Instruction {
+    build(
+        place_bid_accounts(bidder, listing, auction, referrer, gate_nft),
+        instruction::PlaceBid { amount },
+    )
+}
+
+/// Bid on an auction restricted to an allowlist, with the bidder's proof from
+/// `allowlist::proof`
+pub fn place_bid_with_proof(
+    bidder: &Pubkey,
+    listing: &Listing,
+    auction: &Auction,
+    amount: u64,
+    referrer: Option<Pubkey>,
+    gate_nft: Option<Pubkey>,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    build(
+        place_bid_accounts(bidder, listing, auction, referrer, gate_nft),
+        instruction::PlaceBidWithProof { amount, proof },
+    )
+}
+
+fn place_bid_accounts(
+    bidder: &Pubkey,
+    listing: &Listing,
+    auction: &Auction,
+    referrer: Option<Pubkey>,
+    gate_nft: Option<Pubkey>,
+) -> accounts::PlaceBid {
+    let listing_key = pda::find_listing_address(&listing.nft_mint).0;
+    let auction_key = pda::find_auction_address(&listing_key).0;
+    // Any payment mint account on the first bid; refunded only when there is a previous bid
+    let previous_bidder = if auction.current_bid > 0 { &auction.current_bidder } else { bidder };
+    let (gate_token_account, gate_nft_metadata) = gate_accounts(bidder, listing, gate_nft);
+
+    accounts::PlaceBid {
+        marketplace: pda::find_marketplace_address().0,
+        listing: listing_key,
+        auction: auction_key,
+        bidder: *bidder,
+        bidder_payment_account: get_associated_token_address(bidder, &listing.payment_mint),
+        escrow_payment_account: get_associated_token_address(&auction_key, &listing.payment_mint),
+        previous_bidder_account: get_associated_token_address(previous_bidder, &listing.payment_mint),
+        token_program: token::ID,
+        referrer: referrer.map(|wallet| pda::find_referrer_address(&wallet).0),
+        gate_token_account,
+        gate_nft_metadata,
+    }
+}
+
+/// `holder`'s token account and, for collection gates, the NFT metadata that
+/// pass the listing's token gate
+fn gate_accounts(holder: &Pubkey, listing: &Listing, gate_nft: Option<Pubkey>) -> (Option<Pubkey>, Option<Pubkey>) {
+    match (listing.token_gate, gate_nft) {
+        (Some(TokenGate::MinBalance { mint, .. }), _) => (Some(get_associated_token_address(holder, &mint)), None),
+        (Some(TokenGate::Collection(_)), Some(nft)) => (
+            Some(get_associated_token_address(holder, &nft)),
+            Some(pda::find_metadata_address(&nft).0),
+        ),
+        _ => (None, None),
+    }
+}
+
+/// Settle an ended auction; `payer` covers the seller stats account if it is new
+pub fn settle_auction(
+    payer: &Pubkey,
+    marketplace: &Marketplace,
+    listing: &Listing,
+    auction: &Auction,
+    options: &SettleOptions,
+) -> Instruction {
+    let listing_key = pda::find_listing_address(&listing.nft_mint).0;
+    let auction_key = pda::find_auction_address(&listing_key).0;
+    // Without a winner the NFT goes back to the seller and this is only mint-checked
+    let nft_recipient = if auction.reserve_met { &auction.current_bidder } else { &listing.seller };
+    let rewards_vault = rewards_vault(marketplace, listing, auction.current_bid).filter(|_| auction.reserve_met);
+
+    let accounts = accounts::SettleAuction {
+        marketplace: pda::find_marketplace_address().0,
+        listing: listing_key,
+        auction: auction_key,
+        seller_payment_account: get_associated_token_address(&listing.seller, &listing.payment_mint),
+        seller_nft_account: get_associated_token_address(&listing.seller, &listing.nft_mint),
+        fee_collector_account: get_associated_token_address(&marketplace.fee_collector, &listing.payment_mint),
+        winner_nft_account: get_associated_token_address(nft_recipient, &listing.nft_mint),
+        escrow_nft_account: get_associated_token_address(&listing_key, &listing.nft_mint),
+        escrow_payment_account: get_associated_token_address(&auction_key, &listing.payment_mint),
+        token_program: token::ID,
+        rewards_vault,
+        winner_rewards_account: rewards_vault
+            .map(|_| get_associated_token_address(&auction.current_bidder, &marketplace.rewards_mint)),
+        referrer_payment_account: auction
+            .referrer
+            .filter(|_| auction.reserve_met)
+            .map(|wallet| get_associated_token_address(&wallet, &listing.payment_mint)),
+        payer: *payer,
+        seller_stats: pda::find_seller_stats_address(&listing.seller, &listing.payment_mint).0,
+        nft_metadata: options.collection.map(|_| pda::find_metadata_address(&listing.nft_mint).0),
+        collection_stats: options
+            .collection
+            .map(|collection| pda::find_collection_stats_address(&collection, &listing.payment_mint).0),
+        system_program: system_program::ID,
+    };
+    build(accounts, instruction::SettleAuction {})
+}
+
+/// Cancel a listing and return the NFT to the seller
+pub fn cancel_listing(listing: &Listing) -> Instruction {
+    let listing_key = pda::find_listing_address(&listing.nft_mint).0;
+    let accounts = accounts::CancelListing {
+        listing: listing_key,
+        auction: (listing.listing_type == ListingType::Auction)
+            .then(|| pda::find_auction_address(&listing_key).0),
+        seller: listing.seller,
+        seller_nft_account: get_associated_token_address(&listing.seller, &listing.nft_mint),
+        escrow_nft_account: get_associated_token_address(&listing_key, &listing.nft_mint),
+        token_program: token::ID,
+    };
+    build(accounts, instruction::CancelListing {})
+}
+
+/// Return an expired fixed-price listing's NFT to the seller; anyone can send this
+pub fn expire_listing(listing: &Listing) -> Instruction {
+    let listing_key = pda::find_listing_address(&listing.nft_mint).0;
+    let accounts = accounts::ExpireListing {
+        listing: listing_key,
+        seller_nft_account: get_associated_token_address(&listing.seller, &listing.nft_mint),
+        escrow_nft_account: get_associated_token_address(&listing_key, &listing.nft_mint),
+        token_program: token::ID,
+    };
+    build(accounts, instruction::ExpireListing {})
+}
+
+/// Accept other payment mints for a fixed-price listing at their own prices,
+/// replacing any set before
+pub fn set_listing_prices(seller: &Pubkey, nft_mint: &Pubkey, prices: Vec<MintPrice>) -> Instruction {
+    let listing = pda::find_listing_address(nft_mint).0;
+    let accounts = accounts::SetListingPrices {
+        listing,
+        listing_prices: pda::find_listing_prices_address(&listing).0,
+        seller: *seller,
+        system_program: system_program::ID,
+    };
+    build(accounts, instruction::SetListingPrices { prices })
+}
+
+/// Close a listing's extra payment mints, refunding the rent to the seller
+pub fn close_listing_prices(seller: &Pubkey, nft_mint: &Pubkey) -> Instruction {
+    let listing = pda::find_listing_address(nft_mint).0;
+    let accounts = accounts::CloseListingPrices {
+        listing,
+        listing_prices: pda::find_listing_prices_address(&listing).0,
+        seller: *seller,
+    };
+    build(accounts, instruction::CloseListingPrices {})
+}
+
+/// Restrict who can buy or bid on a listing, or lift the restriction with
+/// `None`. Send it in the same transaction as the listing so it is never
+/// open to everyone.
+pub fn set_buyer_restriction(
+    seller: &Pubkey,
+    nft_mint: &Pubkey,
+    listing_type: ListingType,
+    restriction: Option<BuyerRestriction>,
+) -> Instruction {
+    build(
+        listing_access_accounts(seller, nft_mint, listing_type),
+        instruction::SetBuyerRestriction { restriction },
+    )
+}
+
+/// Require buyers and bidders to hold a token, or remove the requirement
+/// with `None`
+pub fn set_token_gate(
+    seller: &Pubkey,
+    nft_mint: &Pubkey,
+    listing_type: ListingType,
+    token_gate: Option<TokenGate>,
+) -> Instruction {
+    build(
+        listing_access_accounts(seller, nft_mint, listing_type),
+        instruction::SetTokenGate { token_gate },
+    )
+}
+
+fn listing_access_accounts(
+    seller: &Pubkey,
+    nft_mint: &Pubkey,
+    listing_type: ListingType,
+) -> accounts::SetListingAccess {
+    let listing = pda::find_listing_address(nft_mint).0;
+    accounts::SetListingAccess {
+        listing,
+        seller: *seller,
+        auction: (listing_type == ListingType::Auction).then(|| pda::find_auction_address(&listing).0),
+    }
+}
+
+/// Pause or unpause trading (pauser only)
+pub fn set_paused(holder: &Pubkey, paused: bool) -> Instruction {
+    build(role_action(holder, Role::Pauser), instruction::SetPaused { paused })
+}
+
+/// Propose a platform fee change, executable after the fee delay (fee manager only)
+pub fn propose_fee(holder: &Pubkey, new_fee: u16) -> Instruction {
+    build(role_action(holder, Role::FeeManager), instruction::ProposeFee { new_fee })
+}
+
+/// Apply the pending platform fee once its timelock has passed (fee manager only)
+pub fn execute_fee(holder: &Pubkey) -> Instruction {
+    build(role_action(holder, Role::FeeManager), instruction::ExecuteFee {})
+}
+
+/// Drop the pending platform fee (fee manager only)
+pub fn cancel_fee(holder: &Pubkey) -> Instruction {
+    build(role_action(holder, Role::FeeManager), instruction::CancelFee {})
+}
+
+/// Accept `payment_mint` for USD listings, priced by the Pyth price update
+/// account `oracle` for `feed_id` (treasury manager only)
+pub fn set_price_feed(
+    holder: &Pubkey,
+    payment_mint: &Pubkey,
+    oracle: Pubkey,
+    feed_id: [u8; 32],
+    max_staleness: i64,
+    max_confidence: u16,
+) -> Instruction {
+    let accounts = accounts::SetPriceFeed {
+        price_feed: pda::find_price_feed_address(payment_mint).0,
+        payment_mint: *payment_mint,
+        role_assignment: pda::find_marketplace_role_address(holder, Role::TreasuryManager).0,
+        holder: *holder,
+        system_program: system_program::ID,
+    };
+    build(accounts, instruction::SetPriceFeed { oracle, feed_id, max_staleness, max_confidence })
+}
+
+/// Remove a payment mint's price feed (treasury manager only)
+pub fn remove_price_feed(holder: &Pubkey, payment_mint: &Pubkey) -> Instruction {
+    let accounts = accounts::RemovePriceFeed {
+        price_feed: pda::find_price_feed_address(payment_mint).0,
+        role_assignment: pda::find_marketplace_role_address(holder, Role::TreasuryManager).0,
+        holder: *holder,
+    };
+    build(accounts, instruction::RemovePriceFeed {})
+}
+
+fn role_action(holder: &Pubkey, role: Role) -> accounts::RoleAction {
+    accounts::RoleAction {
+        marketplace: pda::find_marketplace_address().0,
+        role_assignment: pda::find_marketplace_role_address(holder, role).0,
+        holder: *holder,
+    }
+}
+
+/// Create stats for a seller and payment mint
+pub fn init_seller_stats(payer: &Pubkey, seller: Pubkey, payment_mint: Pubkey) -> Instruction {
+    let accounts = accounts::InitSellerStats {
+        seller_stats: pda::find_seller_stats_address(&seller, &payment_mint).0,
+        payer: *payer,
+        system_program: system_program::ID,
+    };
+    build(accounts, instruction::InitSellerStats { seller, payment_mint })
+}
+
+/// Create stats for a collection and payment mint
+pub fn init_collection_stats(payer: &Pubkey, collection: Pubkey, payment_mint: Pubkey) -> Instruction {
+    let accounts = accounts::InitCollectionStats {
+        collection_stats: pda::find_collection_stats_address(&collection, &payment_mint).0,
+        payer: *payer,
+        system_program: system_program::ID,
+    };
+    build(accounts, instruction::InitCollectionStats { collection, payment_mint })
+}
+
+/// Upgrade the marketplace account to the current layout
+pub fn migrate_marketplace(payer: &Pubkey) -> Instruction {
+    let accounts = accounts::MigrateMarketplace {
+        marketplace: pda::find_marketplace_address().0,
+        payer: *payer,
+        system_program: system_program::ID,
+    };
+    build(accounts, instruction::MigrateMarketplace {})
+}
+
+/// Upgrade a listing account to the current layout
+pub fn migrate_listing(payer: &Pubkey, listing: Pubkey) -> Instruction {
+    build(migrate_accounts(payer, listing), instruction::MigrateListing {})
+}
+
+/// Upgrade an auction account to the current layout
+pub fn migrate_auction(payer: &Pubkey, auction: Pubkey) -> Instruction {
+    build(migrate_accounts(payer, auction), instruction::MigrateAuction {})
+}
+
+fn migrate_accounts(payer: &Pubkey, account: Pubkey) -> accounts::MigrateAccount {
+    accounts::MigrateAccount {
+        account,
+        payer: *payer,
+        system_program: system_program::ID,
+    }
+}
+
+/// Rewards vault when the sale earns trading rewards, which makes it required
+fn rewards_vault(marketplace: &Marketplace, listing: &Listing, price: u64) -> Option<Pubkey> {
+    (marketplace.trading_reward(listing, price) > 0).then_some(marketplace.rewards_vault)
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
@@ -0,0 +1,113 @@
+//! Fee and bid math, matching what the marketplace program computes on-chain
+
+use soundchain_marketplace::{Auction, FeeDiscount, FeeSchedule, Listing, Marketplace};
+
+/// How a sale's price is split between the parties
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SaleBreakdown {
+    /// Total platform fee, including the referrer's share
+    pub platform_fee: u64,
+    /// Part of the platform fee paid to the referrer
+    pub referral_fee: u64,
+    /// Part of the platform fee paid to the fee collector
+    pub fee_collector_amount: u64,
+    pub seller_amount: u64,
+    /// OGUN trading reward paid to each of the buyer and seller
+    pub reward: u64,
+}
+
+/// Fee inputs for a `buy`, as known to the client
+#[derive(Clone, Copy, Default)]
+pub struct FeeInputs<'a> {
+    /// Collection fee override, when the collection has one and it is passed
+    pub collection_fee: Option<u16>,
+    /// Seller's partner fee, when the seller has one and it is passed
+    pub partner_fee: Option<u16>,
+    /// Fee schedule, when discount accounts are passed
+    pub fee_schedule: Option<&'a FeeSchedule>,
+    /// Buyer's OGUN balance, when their OGUN account is passed
+    pub ogun_balance: Option<u64>,
+    /// Buyer's staked OGUN, when their stake account is passed
+    pub staked_amount: Option<u64>,
+}
+
+/// Fee in basis points applied to a `buy`: the lowest of the platform, collection
+/// and partner fees, less the better of the holder and staker discounts
+pub fn buy_fee_bps(marketplace: &Marketplace, inputs: &FeeInputs) -> (u16, FeeDiscount) {
+    let mut fee_bps = marketplace.platform_fee;
+    if let Some(collection_fee) = inputs.collection_fee {
+        fee_bps = fee_bps.min(collection_fee);
+    }
+    if let Some(partner_fee) = inputs.partner_fee {
+        fee_bps = fee_bps.min(partner_fee);
+    }
+
+    let Some(fee_schedule) = inputs.fee_schedule else {
+        return (fee_bps, FeeDiscount::None);
+    };
+
+    let mut discount = (FeeDiscount::None, 0);
+    if let Some(balance) = inputs.ogun_balance {
+        if fee_schedule.holder_discount > 0 && balance >= fee_schedule.holder_threshold {
+            discount = (FeeDiscount::Holder, fee_schedule.holder_discount);
+        }
+    }
+    if let Some(staked) = inputs.staked_amount {
+        if fee_schedule.staker_discount > discount.1 && staked >= fee_schedule.staker_threshold {
+            discount = (FeeDiscount::Staker, fee_schedule.staker_discount);
+        }
+    }
+
+    let reduction = (fee_bps as u32 * discount.1 as u32 / 10000) as u16;
+    (fee_bps - reduction, discount.0)
+}
+
+/// Platform fee on `price` at `fee_bps`
+pub fn platform_fee(price: u64, fee_bps: u16) -> u64 {
+    (price as u128 * fee_bps as u128 / 10000) as u64
+}
+
+/// Referrer's share of a platform fee
+pub fn referral_fee(marketplace: &Marketplace, platform_fee: u64) -> u64 {
+    (platform_fee as u128 * marketplace.referral_share as u128 / 10000) as u64
+}
+
+/// Split of a sale at `price` with `fee_bps`, optionally shared with a referrer
+pub fn sale_breakdown(
+    marketplace: &Marketplace,
+    listing: &Listing,
+    price: u64,
+    fee_bps: u16,
+    with_referrer: bool,
+) -> SaleBreakdown {
+    let platform_fee = platform_fee(price, fee_bps);
+    let referral_fee = if with_referrer { referral_fee(marketplace, platform_fee) } else { 0 };
+    SaleBreakdown {
+        platform_fee,
+        referral_fee,
+        fee_collector_amount: platform_fee - referral_fee,
+        seller_amount: price - platform_fee,
+        reward: marketplace.trading_reward(listing, price),
+    }
+}
+
+/// Split of a settled auction; auctions pay the marketplace's base platform fee
+pub fn auction_breakdown(marketplace: &Marketplace, listing: &Listing, auction: &Auction) -> SaleBreakdown {
+    sale_breakdown(
+        marketplace,
+        listing,
+        auction.current_bid,
+        marketplace.platform_fee,
+        auction.referrer.is_some(),
+    )
+}
+
+/// Lowest amount `place_bid` accepts: above the current bid, and at least 5%
+/// above it once there is one
+pub fn min_bid(auction: &Auction) -> u64 {
+    if auction.current_bid == 0 {
+        return 1;
+    }
+    let min_increase = (auction.current_bid as u128 * 105 / 100) as u64;
+    min_increase.max(auction.current_bid + 1)
+}
@@ -0,0 +1,37 @@
+//! Pyth price updates for USD listings
+//!
+//! `buy_usd` reads a Pyth receiver `PriceUpdateV2` account. On a local
+//! validator or in tests, `stand_in_price_update` builds the same layout so any
+//! account owned by the receiver program can serve as the oracle, e.g. loaded
+//! with `solana-test-validator --account`.
+
+use anchor_lang::AnchorSerialize;
+use soundchain_marketplace::{PythPriceUpdate, PythVerificationLevel, PYTH_PRICE_UPDATE_DISCRIMINATOR};
+
+pub use soundchain_marketplace::usd_to_tokens;
+
+/// Decode a Pyth price update account
+pub fn decode_price_update(data: &[u8]) -> anchor_lang::Result<PythPriceUpdate> {
+    PythPriceUpdate::decode(data)
+}
+
+/// Account data for a fully verified `feed_id` price of `price * 10^exponent`
+/// USD per token, ± `conf`, published at `publish_time`
+pub fn stand_in_price_update(feed_id: [u8; 32], price: i64, conf: u64, exponent: i32, publish_time: i64) -> Vec<u8> {
+    let update = PythPriceUpdate {
+        write_authority: Default::default(),
+        verification_level: PythVerificationLevel::Full,
+        feed_id,
+        price,
+        conf,
+        exponent,
+        publish_time,
+        prev_publish_time: publish_time,
+        ema_price: price,
+        ema_conf: conf,
+        posted_slot: 0,
+    };
+    let mut data = PYTH_PRICE_UPDATE_DISCRIMINATOR.to_vec();
+    update.serialize(&mut data).expect("serializing to a Vec");
+    data
+}
@@ -0,0 +1,119 @@
+//! Program-derived addresses, using the same seeds as the programs
+
+use anchor_lang::prelude::Pubkey;
+
+use soundchain_marketplace::{token_metadata, ID as MARKETPLACE_PROGRAM_ID};
+use soundchain_scid::ID as SCID_PROGRAM_ID;
+
+// ============ Marketplace ============
+
+/// `[b"marketplace"]`
+pub fn find_marketplace_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"marketplace"], &MARKETPLACE_PROGRAM_ID)
+}
+
+/// `[b"listing", nft_mint]`
+pub fn find_listing_address(nft_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"listing", nft_mint.as_ref()], &MARKETPLACE_PROGRAM_ID)
+}
+
+/// `[b"auction", listing]`
+pub fn find_auction_address(listing: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"auction", listing.as_ref()], &MARKETPLACE_PROGRAM_ID)
+}
+
+/// `[b"listing_prices", listing]`
+pub fn find_listing_prices_address(listing: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"listing_prices", listing.as_ref()], &MARKETPLACE_PROGRAM_ID)
+}
+
+/// `[b"seller_stats", seller, payment_mint]`
+pub fn find_seller_stats_address(seller: &Pubkey, payment_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"seller_stats", seller.as_ref(), payment_mint.as_ref()],
+        &MARKETPLACE_PROGRAM_ID,
+    )
+}
+
+/// `[b"collection_stats", collection, payment_mint]`
+pub fn find_collection_stats_address(collection: &Pubkey, payment_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"collection_stats", collection.as_ref(), payment_mint.as_ref()],
+        &MARKETPLACE_PROGRAM_ID,
+    )
+}
+
+/// `[b"fee_schedule"]`
+pub fn find_fee_schedule_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"fee_schedule"], &MARKETPLACE_PROGRAM_ID)
+}
+
+/// `[b"collection_fee", collection]`
+pub fn find_collection_fee_address(collection: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"collection_fee", collection.as_ref()], &MARKETPLACE_PROGRAM_ID)
+}
+
+/// `[b"partner_fee", seller]`
+pub fn find_partner_fee_address(seller: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"partner_fee", seller.as_ref()], &MARKETPLACE_PROGRAM_ID)
+}
+
+/// `[b"referrer", wallet]`
+pub fn find_referrer_address(wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"referrer", wallet.as_ref()], &MARKETPLACE_PROGRAM_ID)
+}
+
+/// `[b"cross_chain"]`
+pub fn find_cross_chain_config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"cross_chain"], &MARKETPLACE_PROGRAM_ID)
+}
+
+/// `[b"price_feed", payment_mint]`
+pub fn find_price_feed_address(payment_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"price_feed", payment_mint.as_ref()], &MARKETPLACE_PROGRAM_ID)
+}
+
+/// `[b"role", holder, role]` in the marketplace program
+pub fn find_marketplace_role_address(holder: &Pubkey, role: soundchain_marketplace::Role) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"role", holder.as_ref(), &[role as u8]], &MARKETPLACE_PROGRAM_ID)
+}
+
+/// Metaplex metadata account for `mint`
+pub fn find_metadata_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"metadata", token_metadata::ID.as_ref(), mint.as_ref()],
+        &token_metadata::ID,
+    )
+}
+
+/// Staking program `[b"stake", pool, staker]`
+pub fn find_stake_address(pool: &Pubkey, staker: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"stake", pool.as_ref(), staker.as_ref()],
+        &soundchain_staking::ID,
+    )
+}
+
+// ============ SCid ============
+
+/// `[b"registry"]`
+pub fn find_registry_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"registry"], &SCID_PROGRAM_ID)
+}
+
+/// `[b"scid", scid]`
+pub fn find_scid_address(scid: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"scid", scid.as_bytes()], &SCID_PROGRAM_ID)
+}
+
+/// `[b"role", holder, role]` in the SCid program
+pub fn find_scid_role_address(holder: &Pubkey, role: soundchain_scid::Role) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"role", holder.as_ref(), &[role as u8]], &SCID_PROGRAM_ID)
+}
+
+// ============ Fee collector ============
+
+/// `[b"fee_collector"]`, the `fee_collector` of the marketplace and registry
+pub fn find_fee_collector_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"fee_collector"], &soundchain_fee_collector::ID)
+}
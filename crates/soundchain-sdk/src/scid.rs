@@ -0,0 +1,125 @@
+//! Instruction builders for the SCid registry program
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+
+use soundchain_scid::{accounts, instruction, Registry, Role, ID};
+
+use crate::pda;
+
+/// Initialize the registry
+pub fn initialize(authority: &Pubkey, fee_collector: Pubkey) -> Instruction {
+    let accounts = accounts::Initialize {
+        registry: pda::find_registry_address().0,
+        authority: *authority,
+        system_program: system_program::ID,
+    };
+    build(accounts, instruction::Initialize { fee_collector })
+}
+
+/// Register an SCid, paying the registry's fee to its fee collector
+pub fn register(
+    owner: &Pubkey,
+    registry: &Registry,
+    scid: String,
+    metadata_hash: String,
+    token_id: u64,
+    nft_mint: Pubkey,
+) -> Instruction {
+    let accounts = accounts::Register {
+        registry: pda::find_registry_address().0,
+        scid_record: pda::find_scid_address(&scid).0,
+        owner: *owner,
+        fee_collector: registry.fee_collector,
+        system_program: system_program::ID,
+    };
+    build(accounts, instruction::Register { scid, metadata_hash, token_id, nft_mint })
+}
+
+/// Transfer an SCid to a new owner
+pub fn transfer(owner: &Pubkey, scid: &str, new_owner: Pubkey) -> Instruction {
+    let accounts = accounts::Transfer {
+        scid_record: pda::find_scid_address(scid).0,
+        owner: *owner,
+    };
+    build(accounts, instruction::Transfer { new_owner })
+}
+
+/// Revoke an SCid as its owner, or as a moderator when `as_moderator` is set
+pub fn revoke(authority: &Pubkey, scid: &str, as_moderator: bool) -> Instruction {
+    let accounts = accounts::Revoke {
+        registry: pda::find_registry_address().0,
+        scid_record: pda::find_scid_address(scid).0,
+        authority: *authority,
+        role_assignment: as_moderator.then(|| pda::find_scid_role_address(authority, Role::Moderator).0),
+    };
+    build(accounts, instruction::Revoke {})
+}
+
+/// Mark an SCid as registered on another chain (cross-chain verifier only)
+pub fn verify_cross_chain(holder: &Pubkey, scid: &str, source_chain: u16, source_tx_hash: [u8; 32]) -> Instruction {
+    let accounts = accounts::VerifyCrossChain {
+        registry: pda::find_registry_address().0,
+        scid_record: pda::find_scid_address(scid).0,
+        role_assignment: pda::find_scid_role_address(holder, Role::CrossChainVerifier).0,
+        holder: *holder,
+    };
+    build(accounts, instruction::VerifyCrossChain { source_chain, source_tx_hash })
+}
+
+/// Pause or unpause registrations (pauser only)
+pub fn set_paused(holder: &Pubkey, paused: bool) -> Instruction {
+    build(role_action(holder, Role::Pauser), instruction::SetPaused { paused })
+}
+
+/// Propose a registration fee change, executable after the fee delay (fee manager only)
+pub fn propose_fee(holder: &Pubkey, new_fee: u64) -> Instruction {
+    build(role_action(holder, Role::FeeManager), instruction::ProposeFee { new_fee })
+}
+
+/// Apply the pending registration fee once its timelock has passed (fee manager only)
+pub fn execute_fee(holder: &Pubkey) -> Instruction {
+    build(role_action(holder, Role::FeeManager), instruction::ExecuteFee {})
+}
+
+/// Drop the pending registration fee (fee manager only)
+pub fn cancel_fee(holder: &Pubkey) -> Instruction {
+    build(role_action(holder, Role::FeeManager), instruction::CancelFee {})
+}
+
+fn role_action(holder: &Pubkey, role: Role) -> accounts::RoleAction {
+    accounts::RoleAction {
+        registry: pda::find_registry_address().0,
+        role_assignment: pda::find_scid_role_address(holder, role).0,
+        holder: *holder,
+    }
+}
+
+/// Upgrade the registry account to the current layout
+pub fn migrate_registry(payer: &Pubkey) -> Instruction {
+    let accounts = accounts::MigrateRegistry {
+        registry: pda::find_registry_address().0,
+        payer: *payer,
+        system_program: system_program::ID,
+    };
+    build(accounts, instruction::MigrateRegistry {})
+}
+
+/// Upgrade an SCid record to the current layout
+pub fn migrate_scid_record(payer: &Pubkey, scid: &str) -> Instruction {
+    let accounts = accounts::MigrateAccount {
+        account: pda::find_scid_address(scid).0,
+        payer: *payer,
+        system_program: system_program::ID,
+    };
+    build(accounts, instruction::MigrateScidRecord {})
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
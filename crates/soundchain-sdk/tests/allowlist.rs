@@ -0,0 +1,45 @@
+//! Allowlist trees built off-chain against the program's proof check
+
+use soundchain_sdk::{allowlist, BuyerRestriction, Pubkey};
+
+fn wallets(count: usize) -> Vec<Pubkey> {
+    (0..count).map(|_| Pubkey::new_unique()).collect()
+}
+
+#[test]
+fn every_member_proves_against_the_root() {
+    for count in [1, 2, 3, 5, 8, 13] {
+        let buyers = wallets(count);
+        let restriction = BuyerRestriction::Allowlist { root: allowlist::root(&buyers) };
+        for buyer in &buyers {
+            let proof = allowlist::proof(&buyers, buyer).unwrap();
+            assert!(restriction.allows(buyer, &proof), "{count} wallets");
+        }
+    }
+}
+
+#[test]
+fn outsiders_and_wrong_proofs_are_rejected() {
+    let buyers = wallets(5);
+    let restriction = BuyerRestriction::Allowlist { root: allowlist::root(&buyers) };
+    let outsider = Pubkey::new_unique();
+    assert!(allowlist::proof(&buyers, &outsider).is_none());
+
+    // Someone else's proof doesn't work for the outsider or another member
+    let proof = allowlist::proof(&buyers, &buyers[0]).unwrap();
+    assert!(!restriction.allows(&outsider, &proof));
+    assert!(!restriction.allows(&buyers[3], &proof));
+    assert!(!restriction.allows(&buyers[0], &[]));
+
+    // An empty allowlist lets no one in
+    let empty = BuyerRestriction::Allowlist { root: allowlist::root(&[]) };
+    assert!(!empty.allows(&buyers[0], &[]));
+}
+
+#[test]
+fn a_reserved_listing_only_allows_its_buyer() {
+    let buyer = Pubkey::new_unique();
+    let restriction = BuyerRestriction::Buyer(buyer);
+    assert!(restriction.allows(&buyer, &[]));
+    assert!(!restriction.allows(&Pubkey::new_unique(), &[]));
+}
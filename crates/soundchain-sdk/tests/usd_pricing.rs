@@ -0,0 +1,87 @@
+//! USD listing prices against a stand-in Pyth price account
+
+use soundchain_marketplace::{ErrorCode, PythVerificationLevel};
+use soundchain_sdk::oracle::{decode_price_update, stand_in_price_update, usd_to_tokens};
+use soundchain_sdk::{PriceFeed, Pubkey};
+
+const NOW: i64 = 1_700_000_000;
+const FEED_ID: [u8; 32] = [7; 32];
+
+/// $25.00
+const USD_PRICE: u64 = 25_000_000;
+
+fn price_feed(decimals: u8) -> PriceFeed {
+    PriceFeed {
+        payment_mint: Pubkey::new_unique(),
+        oracle: Pubkey::new_unique(),
+        decimals,
+        max_staleness: 60,
+        max_confidence: 100,
+        feed_id: FEED_ID,
+    }
+}
+
+#[test]
+fn converts_at_the_oracle_rate_rounding_up() {
+    // SOL at $150.00: $25 is 1/6 SOL
+    let update = decode_price_update(&stand_in_price_update(FEED_ID, 15_000_000_000, 7_500_000, -8, NOW)).unwrap();
+    assert_eq!(usd_to_tokens(USD_PRICE, &update, &price_feed(9), NOW).unwrap(), 166_666_667);
+
+    // A stablecoin slightly above peg
+    let update = decode_price_update(&stand_in_price_update(FEED_ID, 100_010_000, 10_000, -8, NOW)).unwrap();
+    assert_eq!(usd_to_tokens(USD_PRICE, &update, &price_feed(6), NOW).unwrap(), 24_997_501);
+}
+
+#[test]
+fn rejects_stale_prices() {
+    let update = decode_price_update(&stand_in_price_update(FEED_ID, 15_000_000_000, 0, -8, NOW - 60)).unwrap();
+    assert!(usd_to_tokens(USD_PRICE, &update, &price_feed(9), NOW).is_ok());
+
+    let update = decode_price_update(&stand_in_price_update(FEED_ID, 15_000_000_000, 0, -8, NOW - 61)).unwrap();
+    assert_eq!(
+        usd_to_tokens(USD_PRICE, &update, &price_feed(9), NOW).unwrap_err(),
+        ErrorCode::StaleOraclePrice.into()
+    );
+}
+
+#[test]
+fn rejects_wide_confidence_intervals() {
+    // 1% of the price is the limit
+    let update = decode_price_update(&stand_in_price_update(FEED_ID, 15_000_000_000, 150_000_000, -8, NOW)).unwrap();
+    assert!(usd_to_tokens(USD_PRICE, &update, &price_feed(9), NOW).is_ok());
+
+    let update = decode_price_update(&stand_in_price_update(FEED_ID, 15_000_000_000, 150_000_001, -8, NOW)).unwrap();
+    assert_eq!(
+        usd_to_tokens(USD_PRICE, &update, &price_feed(9), NOW).unwrap_err(),
+        ErrorCode::OracleConfidenceTooWide.into()
+    );
+}
+
+#[test]
+fn rejects_unverified_or_invalid_prices() {
+    let mut update = decode_price_update(&stand_in_price_update(FEED_ID, 15_000_000_000, 0, -8, NOW)).unwrap();
+    update.verification_level = PythVerificationLevel::Partial { num_signatures: 3 };
+    assert_eq!(
+        usd_to_tokens(USD_PRICE, &update, &price_feed(9), NOW).unwrap_err(),
+        ErrorCode::InvalidPriceOracle.into()
+    );
+
+    let update = decode_price_update(&stand_in_price_update(FEED_ID, 0, 0, -8, NOW)).unwrap();
+    assert_eq!(
+        usd_to_tokens(USD_PRICE, &update, &price_feed(9), NOW).unwrap_err(),
+        ErrorCode::InvalidPriceOracle.into()
+    );
+
+    let mut data = stand_in_price_update(FEED_ID, 15_000_000_000, 0, -8, NOW);
+    data[0] ^= 1;
+    assert!(decode_price_update(&data).is_err());
+}
+
+#[test]
+fn rejects_updates_for_another_feed() {
+    let update = decode_price_update(&stand_in_price_update([8; 32], 15_000_000_000, 0, -8, NOW)).unwrap();
+    assert_eq!(
+        usd_to_tokens(USD_PRICE, &update, &price_feed(9), NOW).unwrap_err(),
+        ErrorCode::OracleFeedMismatch.into()
+    );
+}
@@ -0,0 +1,738 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use anchor_lang::system_program::{self, Transfer as SystemTransfer};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer as TokenTransfer};
+use anchor_spl::associated_token::AssociatedToken;
+
+declare_id!("Bsr83u4uFzedJhV4BsCpFcM4gN1XeASZhYg8BkU6BD1T");
+
+/// Metaplex Token Metadata program
+pub mod token_metadata {
+    use super::*;
+    declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+}
+
+/// Owners can reclaim an unprocessed bridge request after 24 hours
+pub const BRIDGE_TIMEOUT: i64 = 86_400;
+
+/// SoundChain NFT Bridge for Solana
+///
+/// Port of SoundchainNFTBridge.sol so tracks minted on Solana can reach the
+/// Polygon and Base deployments.
+///
+/// Bridge Flow:
+/// 1. Owner locks an NFT from a whitelisted collection in the bridge vault
+/// 2. Bridge emits NftLocked with the target chain, recipient and token URI
+/// 3. Relayer picks up the event, calls acknowledge_bridge and mints the
+///    wrapped NFT on the target chain
+/// 4. When the wrapped NFT is burned, a relayer calls unlock_nft to release it
+/// 5. OR: the owner cancels a request no relayer acknowledged once
+///    BRIDGE_TIMEOUT has passed
+///
+/// The vault is the bridge config PDA's ATA for each locked mint.
+#[program]
+pub mod soundchain_bridge {
+    use super::*;
+
+    /// Initialize the bridge
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        chain_id: u64,
+        fee_collector: Pubkey,
+        bridge_fee: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.chain_id = chain_id; // ZetaChain chain ID for this Solana cluster
+        config.fee_collector = fee_collector;
+        config.bridge_fee = bridge_fee; // Lamports
+        config.next_nonce = 0;
+        config.paused = false;
+        Ok(())
+    }
+
+    /// Lock an NFT in the vault and request a bridge to another chain
+    pub fn lock_and_bridge(
+        ctx: Context<LockAndBridge>,
+        target_chain: u64,
+        recipient: [u8; 20],
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(!config.paused, ErrorCode::BridgePaused);
+        require!(target_chain != config.chain_id, ErrorCode::SameChain);
+        require!(recipient != [0u8; 20], ErrorCode::InvalidRecipient);
+
+        let mint = &ctx.accounts.mint;
+        require!(mint.decimals == 0 && mint.supply == 1, ErrorCode::NotAnNft);
+
+        // Only NFTs from a verified, whitelisted collection can be bridged
+        let metadata = read_metadata(&ctx.accounts.metadata)?;
+        let collection = metadata
+            .collection
+            .filter(|collection| collection.verified)
+            .ok_or(ErrorCode::CollectionNotVerified)?;
+        require_keys_eq!(
+            collection.key,
+            ctx.accounts.whitelisted_collection.collection,
+            ErrorCode::CollectionNotWhitelisted
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let owner = ctx.accounts.owner.key();
+        let nonce = config.next_nonce;
+        config.next_nonce += 1;
+
+        let bridge_id = keccak::hashv(&[
+            &config.chain_id.to_le_bytes(),
+            &target_chain.to_le_bytes(),
+            mint.key().as_ref(),
+            owner.as_ref(),
+            &now.to_le_bytes(),
+            &nonce.to_le_bytes(),
+        ])
+        .to_bytes();
+
+        // Lock NFT
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.owner_nft_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, 1)?;
+
+        // Collect fee
+        if config.bridge_fee > 0 {
+            let fee_accounts = SystemTransfer {
+                from: ctx.accounts.owner.to_account_info(),
+                to: ctx.accounts.fee_collector.to_account_info(),
+            };
+            let fee_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), fee_accounts);
+            system_program::transfer(fee_ctx, config.bridge_fee)?;
+        }
+
+        let request = &mut ctx.accounts.request;
+        request.bridge_id = bridge_id;
+        request.nonce = nonce;
+        request.source_chain = config.chain_id;
+        request.target_chain = target_chain;
+        request.mint = mint.key();
+        request.collection = collection.key;
+        request.owner = owner;
+        request.recipient = recipient;
+        request.timestamp = now;
+        request.completed = false;
+        request.acknowledged = false;
+
+        emit!(NftLocked {
+            bridge_id,
+            source_chain: config.chain_id,
+            target_chain,
+            mint: request.mint,
+            collection: request.collection,
+            owner,
+            recipient,
+            token_uri: metadata.uri.trim_end_matches('\0').to_string(),
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Release a locked NFT after its wrapped copy is burned (relayer only)
+    pub fn unlock_nft(ctx: Context<UnlockNft>, bridge_id: [u8; 32]) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(!config.paused, ErrorCode::BridgePaused);
+
+        let request = &mut ctx.accounts.request;
+        require!(request.source_chain == config.chain_id, ErrorCode::WrongSourceChain);
+        request.complete()?;
+
+        release_nft(
+            &ctx.accounts.config,
+            ctx.bumps.config,
+            &ctx.accounts.vault,
+            &ctx.accounts.recipient_nft_account,
+            &ctx.accounts.token_program,
+        )?;
+
+        let request = &ctx.accounts.request;
+
+        emit!(NftUnlocked {
+            bridge_id,
+            mint: request.mint,
+            recipient: ctx.accounts.recipient.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Mark a bridge request as picked up before minting the wrapped NFT, so
+    /// the owner can no longer cancel it (relayer only)
+    pub fn acknowledge_bridge(ctx: Context<AcknowledgeBridge>, bridge_id: [u8; 32]) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(!config.paused, ErrorCode::BridgePaused);
+
+        let request = &mut ctx.accounts.request;
+        require!(request.source_chain == config.chain_id, ErrorCode::WrongSourceChain);
+        request.acknowledge()?;
+
+        emit!(BridgeAcknowledged {
+            bridge_id,
+            relayer: ctx.accounts.relayer.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel an unacknowledged bridge request and return the NFT (owner only)
+    pub fn cancel_bridge(ctx: Context<CancelBridge>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let request = &mut ctx.accounts.request;
+        require!(request.source_chain == config.chain_id, ErrorCode::WrongSourceChain);
+        request.cancel(Clock::get()?.unix_timestamp)?;
+
+        release_nft(
+            &ctx.accounts.config,
+            ctx.bumps.config,
+            &ctx.accounts.vault,
+            &ctx.accounts.owner_nft_account,
+            &ctx.accounts.token_program,
+        )?;
+
+        let request = &ctx.accounts.request;
+
+        emit!(NftUnlocked {
+            bridge_id: request.bridge_id,
+            mint: request.mint,
+            recipient: request.owner,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Authorize a relayer (admin only)
+    pub fn add_relayer(ctx: Context<AddRelayer>, relayer: Pubkey) -> Result<()> {
+        ctx.accounts.relayer_authorization.relayer = relayer;
+
+        emit!(RelayerUpdated {
+            relayer,
+            authorized: true,
+        });
+
+        Ok(())
+    }
+
+    /// Revoke a relayer (admin only)
+    pub fn remove_relayer(ctx: Context<RemoveRelayer>) -> Result<()> {
+        emit!(RelayerUpdated {
+            relayer: ctx.accounts.relayer_authorization.relayer,
+            authorized: false,
+        });
+
+        Ok(())
+    }
+
+    /// Enable a destination chain (admin only)
+    pub fn add_supported_chain(ctx: Context<AddSupportedChain>, chain_id: u64) -> Result<()> {
+        ctx.accounts.supported_chain.chain_id = chain_id;
+        Ok(())
+    }
+
+    /// Disable a destination chain (admin only)
+    pub fn remove_supported_chain(_ctx: Context<RemoveSupportedChain>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Whitelist a verified collection for bridging (admin only)
+    pub fn whitelist_collection(ctx: Context<WhitelistCollection>, collection: Pubkey) -> Result<()> {
+        ctx.accounts.whitelisted_collection.collection = collection;
+        Ok(())
+    }
+
+    /// Remove a collection from the whitelist (admin only)
+    pub fn remove_collection(_ctx: Context<RemoveCollection>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Update bridge fee (admin only)
+    pub fn set_bridge_fee(ctx: Context<AdminAction>, new_fee: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_fee = config.bridge_fee;
+        config.bridge_fee = new_fee;
+
+        emit!(BridgeFeeUpdated { old_fee, new_fee });
+
+        Ok(())
+    }
+
+    /// Update fee collector (admin only)
+    pub fn set_fee_collector(ctx: Context<AdminAction>, new_collector: Pubkey) -> Result<()> {
+        require!(new_collector != Pubkey::default(), ErrorCode::InvalidFeeCollector);
+        let config = &mut ctx.accounts.config;
+        config.fee_collector = new_collector;
+        Ok(())
+    }
+
+    /// Pause/unpause bridge (admin only)
+    pub fn set_paused(ctx: Context<AdminAction>, paused: bool) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.paused = paused;
+        Ok(())
+    }
+}
+
+// ============ Helpers ============
+
+/// Leading fields of a Metaplex metadata account, up to the collection
+#[derive(AnchorDeserialize)]
+struct MetadataPrefix {
+    _key: u8,
+    _update_authority: Pubkey,
+    _mint: Pubkey,
+    _name: String,
+    _symbol: String,
+    uri: String,
+    _seller_fee_basis_points: u16,
+    _creators: Option<Vec<MetadataCreator>>,
+    _primary_sale_happened: bool,
+    _is_mutable: bool,
+    _edition_nonce: Option<u8>,
+    _token_standard: Option<u8>,
+    collection: Option<MetadataCollection>,
+}
+
+#[derive(AnchorDeserialize)]
+struct MetadataCreator {
+    _address: Pubkey,
+    _verified: bool,
+    _share: u8,
+}
+
+#[derive(AnchorDeserialize)]
+struct MetadataCollection {
+    verified: bool,
+    key: Pubkey,
+}
+
+/// Decode the parts of a metadata account the bridge needs
+fn read_metadata(metadata: &AccountInfo) -> Result<MetadataPrefix> {
+    let data = metadata.try_borrow_data()?;
+    MetadataPrefix::deserialize(&mut &data[..]).map_err(|_| error!(ErrorCode::InvalidMetadata))
+}
+
+/// Transfer a locked NFT out of the vault
+fn release_nft<'info>(
+    config: &Account<'info, BridgeConfig>,
+    config_bump: u8,
+    vault: &Account<'info, TokenAccount>,
+    destination: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    let seeds = &[b"bridge_config".as_ref(), &[config_bump]];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = TokenTransfer {
+        from: vault.to_account_info(),
+        to: destination.to_account_info(),
+        authority: config.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer);
+    token::transfer(cpi_ctx, 1)
+}
+
+// ============ Accounts ============
+
+#[account]
+pub struct BridgeConfig {
+    pub authority: Pubkey,
+    pub chain_id: u64,
+    pub fee_collector: Pubkey,
+    pub bridge_fee: u64, // Lamports
+    pub next_nonce: u64,
+    pub paused: bool,
+}
+
+#[account]
+pub struct RelayerAuthorization {
+    pub relayer: Pubkey,
+}
+
+#[account]
+pub struct SupportedChain {
+    pub chain_id: u64,
+}
+
+#[account]
+pub struct WhitelistedCollection {
+    pub collection: Pubkey,
+}
+
+#[account]
+pub struct BridgeRequest {
+    pub bridge_id: [u8; 32],
+    pub nonce: u64,
+    pub source_chain: u64,
+    pub target_chain: u64,
+    pub mint: Pubkey,
+    pub collection: Pubkey,
+    pub owner: Pubkey,
+    pub recipient: [u8; 20], // EVM address on the target chain
+    pub timestamp: i64,
+    pub completed: bool,
+    pub acknowledged: bool, // A relayer is minting the wrapped NFT
+}
+
+impl BridgeRequest {
+    /// Record a relayer picking up the request; blocks `cancel`
+    pub fn acknowledge(&mut self) -> Result<()> {
+        require!(!self.completed, ErrorCode::AlreadyCompleted);
+        require!(!self.acknowledged, ErrorCode::AlreadyAcknowledged);
+        self.acknowledged = true;
+        Ok(())
+    }
+
+    /// Close the request when its NFT is unlocked; each request releases once
+    pub fn complete(&mut self) -> Result<()> {
+        require!(!self.completed, ErrorCode::AlreadyCompleted);
+        self.completed = true;
+        Ok(())
+    }
+
+    /// Close an unacknowledged request for the owner once BRIDGE_TIMEOUT has passed
+    pub fn cancel(&mut self, now: i64) -> Result<()> {
+        require!(!self.acknowledged, ErrorCode::BridgeInProgress);
+        require!(now > self.timestamp + BRIDGE_TIMEOUT, ErrorCode::TimeoutNotReached);
+        self.complete()
+    }
+}
+
+// ============ Contexts ============
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 8 + 32 + 8 + 8 + 1,
+        seeds = [b"bridge_config"],
+        bump
+    )]
+    pub config: Account<'info, BridgeConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(target_chain: u64)]
+pub struct LockAndBridge<'info> {
+    #[account(mut, seeds = [b"bridge_config"], bump)]
+    pub config: Account<'info, BridgeConfig>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 32 + 8 + 8 + 8 + 32 + 32 + 32 + 20 + 8 + 1 + 1,
+        seeds = [b"bridge_request", config.next_nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub request: Account<'info, BridgeRequest>,
+    #[account(seeds = [b"supported_chain", target_chain.to_le_bytes().as_ref()], bump)]
+    pub supported_chain: Account<'info, SupportedChain>,
+    #[account(
+        seeds = [b"collection", whitelisted_collection.collection.as_ref()],
+        bump
+    )]
+    pub whitelisted_collection: Account<'info, WhitelistedCollection>,
+    pub mint: Account<'info, Mint>,
+    /// CHECK: Metaplex metadata PDA of `mint`, decoded in the handler
+    #[account(
+        seeds = [b"metadata", token_metadata::ID.as_ref(), mint.key().as_ref()],
+        bump,
+        seeds::program = token_metadata::ID,
+        owner = token_metadata::ID
+    )]
+    pub metadata: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub owner_nft_account: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = config
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    /// CHECK: Receives the bridge fee
+    #[account(mut, address = config.fee_collector)]
+    pub fee_collector: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(bridge_id: [u8; 32])]
+pub struct UnlockNft<'info> {
+    #[account(seeds = [b"bridge_config"], bump)]
+    pub config: Account<'info, BridgeConfig>,
+    #[account(
+        mut,
+        seeds = [b"bridge_request", request.nonce.to_le_bytes().as_ref()],
+        bump,
+        constraint = request.bridge_id == bridge_id @ ErrorCode::BridgeIdMismatch
+    )]
+    pub request: Account<'info, BridgeRequest>,
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+    #[account(seeds = [b"relayer", relayer.key().as_ref()], bump)]
+    pub relayer_authorization: Account<'info, RelayerAuthorization>,
+    #[account(address = request.mint)]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = config
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    /// CHECK: Solana recipient of the returning NFT
+    pub recipient: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        associated_token::mint = mint,
+        associated_token::authority = recipient
+    )]
+    pub recipient_nft_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(bridge_id: [u8; 32])]
+pub struct AcknowledgeBridge<'info> {
+    #[account(seeds = [b"bridge_config"], bump)]
+    pub config: Account<'info, BridgeConfig>,
+    #[account(
+        mut,
+        seeds = [b"bridge_request", request.nonce.to_le_bytes().as_ref()],
+        bump,
+        constraint = request.bridge_id == bridge_id @ ErrorCode::BridgeIdMismatch
+    )]
+    pub request: Account<'info, BridgeRequest>,
+    pub relayer: Signer<'info>,
+    #[account(seeds = [b"relayer", relayer.key().as_ref()], bump)]
+    pub relayer_authorization: Account<'info, RelayerAuthorization>,
+}
+
+#[derive(Accounts)]
+pub struct CancelBridge<'info> {
+    #[account(seeds = [b"bridge_config"], bump)]
+    pub config: Account<'info, BridgeConfig>,
+    #[account(
+        mut,
+        seeds = [b"bridge_request", request.nonce.to_le_bytes().as_ref()],
+        bump,
+        has_one = owner
+    )]
+    pub request: Account<'info, BridgeRequest>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        associated_token::mint = request.mint,
+        associated_token::authority = config
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = request.mint, token::authority = owner)]
+    pub owner_nft_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(relayer: Pubkey)]
+pub struct AddRelayer<'info> {
+    #[account(seeds = [b"bridge_config"], bump, has_one = authority)]
+    pub config: Account<'info, BridgeConfig>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32,
+        seeds = [b"relayer", relayer.as_ref()],
+        bump
+    )]
+    pub relayer_authorization: Account<'info, RelayerAuthorization>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveRelayer<'info> {
+    #[account(seeds = [b"bridge_config"], bump, has_one = authority)]
+    pub config: Account<'info, BridgeConfig>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"relayer", relayer_authorization.relayer.as_ref()],
+        bump
+    )]
+    pub relayer_authorization: Account<'info, RelayerAuthorization>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(chain_id: u64)]
+pub struct AddSupportedChain<'info> {
+    #[account(seeds = [b"bridge_config"], bump, has_one = authority)]
+    pub config: Account<'info, BridgeConfig>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 8,
+        seeds = [b"supported_chain", chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub supported_chain: Account<'info, SupportedChain>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveSupportedChain<'info> {
+    #[account(seeds = [b"bridge_config"], bump, has_one = authority)]
+    pub config: Account<'info, BridgeConfig>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"supported_chain", supported_chain.chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub supported_chain: Account<'info, SupportedChain>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(collection: Pubkey)]
+pub struct WhitelistCollection<'info> {
+    #[account(seeds = [b"bridge_config"], bump, has_one = authority)]
+    pub config: Account<'info, BridgeConfig>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32,
+        seeds = [b"collection", collection.as_ref()],
+        bump
+    )]
+    pub whitelisted_collection: Account<'info, WhitelistedCollection>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveCollection<'info> {
+    #[account(seeds = [b"bridge_config"], bump, has_one = authority)]
+    pub config: Account<'info, BridgeConfig>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"collection", whitelisted_collection.collection.as_ref()],
+        bump
+    )]
+    pub whitelisted_collection: Account<'info, WhitelistedCollection>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdminAction<'info> {
+    #[account(mut, seeds = [b"bridge_config"], bump, has_one = authority)]
+    pub config: Account<'info, BridgeConfig>,
+    pub authority: Signer<'info>,
+}
+
+// ============ Events ============
+
+#[event]
+pub struct NftLocked {
+    pub bridge_id: [u8; 32],
+    pub source_chain: u64,
+    pub target_chain: u64,
+    pub mint: Pubkey,
+    pub collection: Pubkey,
+    pub owner: Pubkey,
+    pub recipient: [u8; 20],
+    pub token_uri: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NftUnlocked {
+    pub bridge_id: [u8; 32],
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BridgeAcknowledged {
+    pub bridge_id: [u8; 32],
+    pub relayer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RelayerUpdated {
+    pub relayer: Pubkey,
+    pub authorized: bool,
+}
+
+#[event]
+pub struct BridgeFeeUpdated {
+    pub old_fee: u64,
+    pub new_fee: u64,
+}
+
+// ============ Errors ============
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Bridge is paused")]
+    BridgePaused,
+    #[msg("Cannot bridge to same chain")]
+    SameChain,
+    #[msg("Invalid recipient")]
+    InvalidRecipient,
+    #[msg("Mint is not an NFT")]
+    NotAnNft,
+    #[msg("Invalid metadata account")]
+    InvalidMetadata,
+    #[msg("NFT is not part of a verified collection")]
+    CollectionNotVerified,
+    #[msg("Collection not whitelisted")]
+    CollectionNotWhitelisted,
+    #[msg("Bridge ID does not match request")]
+    BridgeIdMismatch,
+    #[msg("Wrong source chain")]
+    WrongSourceChain,
+    #[msg("Already completed")]
+    AlreadyCompleted,
+    #[msg("Must wait 24 hours")]
+    TimeoutNotReached,
+    #[msg("Invalid fee collector")]
+    InvalidFeeCollector,
+    #[msg("Bridge request already acknowledged")]
+    AlreadyAcknowledged,
+    #[msg("A relayer has picked up this bridge request")]
+    BridgeInProgress,
+}
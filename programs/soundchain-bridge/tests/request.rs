@@ -0,0 +1,66 @@
+//! Bridge request replay guard, acknowledgement and timeout
+
+use anchor_lang::prelude::Pubkey;
+use soundchain_bridge::{BridgeRequest, ErrorCode, BRIDGE_TIMEOUT};
+
+const LOCKED_AT: i64 = 1_700_000_000;
+
+fn request() -> BridgeRequest {
+    BridgeRequest {
+        bridge_id: [1; 32],
+        nonce: 0,
+        source_chain: 7000,
+        target_chain: 137,
+        mint: Pubkey::new_unique(),
+        collection: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+        recipient: [2; 20],
+        timestamp: LOCKED_AT,
+        completed: false,
+        acknowledged: false,
+    }
+}
+
+#[test]
+fn a_request_unlocks_once() {
+    let mut request = request();
+    request.acknowledge().unwrap();
+    request.complete().unwrap();
+    assert!(request.completed);
+    assert_eq!(request.complete().unwrap_err(), ErrorCode::AlreadyCompleted.into());
+    assert_eq!(
+        request.cancel(LOCKED_AT + BRIDGE_TIMEOUT + 1).unwrap_err(),
+        ErrorCode::BridgeInProgress.into()
+    );
+}
+
+#[test]
+fn owners_cancel_only_after_the_timeout() {
+    let mut request = request();
+    assert_eq!(
+        request.cancel(LOCKED_AT + BRIDGE_TIMEOUT).unwrap_err(),
+        ErrorCode::TimeoutNotReached.into()
+    );
+    request.cancel(LOCKED_AT + BRIDGE_TIMEOUT + 1).unwrap();
+    assert!(request.completed);
+
+    // A cancelled request can't be unlocked or cancelled again
+    assert_eq!(request.complete().unwrap_err(), ErrorCode::AlreadyCompleted.into());
+    assert_eq!(
+        request.cancel(LOCKED_AT + 2 * BRIDGE_TIMEOUT).unwrap_err(),
+        ErrorCode::AlreadyCompleted.into()
+    );
+    assert_eq!(request.acknowledge().unwrap_err(), ErrorCode::AlreadyCompleted.into());
+}
+
+#[test]
+fn acknowledged_requests_cannot_be_cancelled() {
+    let mut request = request();
+    request.acknowledge().unwrap();
+    assert_eq!(request.acknowledge().unwrap_err(), ErrorCode::AlreadyAcknowledged.into());
+    assert_eq!(
+        request.cancel(LOCKED_AT + 30 * BRIDGE_TIMEOUT).unwrap_err(),
+        ErrorCode::BridgeInProgress.into()
+    );
+    assert!(!request.completed);
+}
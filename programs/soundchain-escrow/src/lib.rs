@@ -0,0 +1,850 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer as TokenTransfer};
+use anchor_spl::associated_token::AssociatedToken;
+use soundchain_marketplace::Marketplace;
+
+declare_id!("BaPVeKnio1DvPE4dfqiHLfegGbWnEiAYFPg3uKPfkBeK");
+
+pub const MAX_TITLE_LEN: usize = 64;
+pub const MAX_URI_LEN: usize = 200;
+pub const MAX_TRACKING_LEN: usize = 64;
+pub const MAX_REASON_LEN: usize = 200;
+
+/// Confirmation window bounds (3 - 90 days)
+pub const MIN_CONFIRMATION_WINDOW: i64 = 259_200;
+pub const MAX_CONFIRMATION_WINDOW: i64 = 7_776_000;
+
+/// SoundChain Physical Item Escrow for Solana
+///
+/// Port of CustomEscrow.sol for vinyl, merch, tickets and other physical items.
+///
+/// Flow:
+/// 1. Seller creates a listing priced in an accepted SPL mint
+/// 2. Buyer purchases, payment is held in an escrow account owned by the order
+/// 3. Seller marks the order shipped with tracking info
+/// 4. Buyer confirms receipt and funds are released to the seller
+/// 5. OR: anyone calls auto_release once the confirmation window has passed
+/// 6. OR: buyer disputes and the admin resolves it
+///
+/// Platform fees on completed sales go to the marketplace fee collector.
+#[program]
+pub mod soundchain_escrow {
+    use super::*;
+
+    /// Initialize the escrow
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        platform_fee: u16,
+        confirmation_window: i64,
+    ) -> Result<()> {
+        require!(platform_fee <= 100, ErrorCode::FeeTooHigh); // Max 1%
+        require!(
+            (MIN_CONFIRMATION_WINDOW..=MAX_CONFIRMATION_WINDOW).contains(&confirmation_window),
+            ErrorCode::InvalidConfirmationWindow
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.platform_fee = platform_fee; // Basis points (5 = 0.05%)
+        config.confirmation_window = confirmation_window;
+        config.next_listing_id = 1;
+        config.next_order_id = 1;
+        config.paused = false;
+        Ok(())
+    }
+
+    /// Create a physical item listing
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_listing(
+        ctx: Context<CreateListing>,
+        item_type: ItemType,
+        title: String,
+        metadata_uri: String,
+        price: u64,
+        quantity: u32,
+        requires_shipping: bool,
+        duration: i64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(!config.paused, ErrorCode::EscrowPaused);
+        require!(price > 0, ErrorCode::InvalidPrice);
+        require!(quantity > 0, ErrorCode::InvalidQuantity);
+        require!(duration > 0 && duration <= 31_536_000, ErrorCode::InvalidDuration); // Max 365 days
+        require!(!title.is_empty() && title.len() <= MAX_TITLE_LEN, ErrorCode::InvalidTitle);
+        require!(metadata_uri.len() <= MAX_URI_LEN, ErrorCode::UriTooLong);
+
+        let listing_id = config.next_listing_id;
+        config.next_listing_id += 1;
+
+        let now = Clock::get()?.unix_timestamp;
+        let listing = &mut ctx.accounts.listing;
+        listing.listing_id = listing_id;
+        listing.seller = ctx.accounts.seller.key();
+        listing.item_type = item_type;
+        listing.title = title;
+        listing.metadata_uri = metadata_uri;
+        listing.price = price;
+        listing.payment_mint = ctx.accounts.payment_mint.key();
+        listing.quantity = quantity;
+        listing.sold = 0;
+        listing.requires_shipping = requires_shipping;
+        listing.created_at = now;
+        listing.expires_at = now + duration;
+        listing.active = true;
+
+        emit!(ItemListed {
+            listing_id,
+            seller: listing.seller,
+            item_type,
+            price,
+            payment_mint: listing.payment_mint,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel a listing (seller or admin)
+    pub fn cancel_listing(ctx: Context<CancelListing>) -> Result<()> {
+        let listing = &mut ctx.accounts.listing;
+        let caller = ctx.accounts.caller.key();
+        require!(
+            caller == listing.seller || caller == ctx.accounts.config.authority,
+            ErrorCode::NotAuthorized
+        );
+        require!(listing.active, ErrorCode::ListingNotActive);
+
+        listing.active = false;
+
+        emit!(ItemListingCancelled {
+            listing_id: listing.listing_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Purchase an item, holding payment in escrow
+    ///
+    /// `shipping_address_hash` commits to the encrypted address the buyer
+    /// sends to the seller off-chain.
+    pub fn purchase(ctx: Context<Purchase>, shipping_address_hash: [u8; 32]) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let listing = &mut ctx.accounts.listing;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(!config.paused, ErrorCode::EscrowPaused);
+        require!(listing.active, ErrorCode::ListingNotActive);
+        require!(now < listing.expires_at, ErrorCode::ListingExpired);
+        require!(listing.sold < listing.quantity, ErrorCode::SoldOut);
+        require!(
+            ctx.accounts.buyer.key() != listing.seller,
+            ErrorCode::CannotBuyOwnListing
+        );
+
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.buyer_payment_account.to_account_info(),
+            to: ctx.accounts.escrow_payment_account.to_account_info(),
+            authority: ctx.accounts.buyer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, listing.price)?;
+
+        let order_id = config.next_order_id;
+        config.next_order_id += 1;
+
+        listing.sold += 1;
+        if listing.sold >= listing.quantity {
+            listing.active = false;
+        }
+
+        let order = &mut ctx.accounts.order;
+        order.order_id = order_id;
+        order.listing_id = listing.listing_id;
+        order.buyer = ctx.accounts.buyer.key();
+        order.seller = listing.seller;
+        order.payment_mint = listing.payment_mint;
+        order.amount = listing.price;
+        order.status = OrderStatus::Paid;
+        order.shipping_address_hash = shipping_address_hash;
+        order.tracking_info = String::new();
+        order.paid_at = now;
+        order.shipped_at = 0;
+        order.confirmed_at = 0;
+        order.disputed_at = 0;
+        order.dispute_reason = String::new();
+        order.auto_release_at = 0; // Set when shipped
+
+        emit!(OrderCreated {
+            order_id,
+            listing_id: listing.listing_id,
+            buyer: order.buyer,
+            amount: order.amount,
+            payment_mint: order.payment_mint,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Mark an order as shipped (seller only)
+    pub fn mark_shipped(ctx: Context<MarkShipped>, tracking_info: String) -> Result<()> {
+        require!(tracking_info.len() <= MAX_TRACKING_LEN, ErrorCode::TrackingInfoTooLong);
+
+        let order = &mut ctx.accounts.order;
+        require!(order.status == OrderStatus::Paid, ErrorCode::InvalidOrderStatus);
+
+        let now = Clock::get()?.unix_timestamp;
+        order.status = OrderStatus::Shipped;
+        order.shipped_at = now;
+        order.tracking_info = tracking_info.clone();
+        // Confirmation window starts from ship date
+        order.auto_release_at = now + ctx.accounts.config.confirmation_window;
+
+        emit!(OrderShipped {
+            order_id: order.order_id,
+            tracking_info,
+            auto_release_at: order.auto_release_at,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Open a dispute (buyer only, before release)
+    pub fn dispute(ctx: Context<Dispute>, reason: String) -> Result<()> {
+        require!(!reason.is_empty(), ErrorCode::ReasonRequired);
+        require!(reason.len() <= MAX_REASON_LEN, ErrorCode::ReasonTooLong);
+
+        let order = &mut ctx.accounts.order;
+        require!(
+            order.status == OrderStatus::Paid || order.status == OrderStatus::Shipped,
+            ErrorCode::InvalidOrderStatus
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        order.status = OrderStatus::Disputed;
+        order.disputed_at = now;
+        order.dispute_reason = reason.clone();
+        // Auto-release is paused during a dispute
+        order.auto_release_at = 0;
+
+        emit!(OrderDisputed {
+            order_id: order.order_id,
+            reason,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Confirm receipt and release funds to the seller (buyer only)
+    pub fn confirm_receipt(ctx: Context<SettleOrder>) -> Result<()> {
+        let order = &ctx.accounts.order;
+        require!(ctx.accounts.caller.key() == order.buyer, ErrorCode::NotBuyer);
+        require!(
+            order.status == OrderStatus::Paid || order.status == OrderStatus::Shipped,
+            ErrorCode::InvalidOrderStatus
+        );
+
+        let fee = release_funds(&ctx)?;
+
+        let order = &mut ctx.accounts.order;
+        order.status = OrderStatus::Confirmed;
+        order.confirmed_at = Clock::get()?.unix_timestamp;
+
+        emit!(OrderReleased {
+            order_id: order.order_id,
+            seller: order.seller,
+            amount: order.amount,
+            platform_fee: fee,
+            status: order.status,
+            timestamp: order.confirmed_at,
+        });
+
+        Ok(())
+    }
+
+    /// Release funds after the confirmation window (permissionless crank)
+    pub fn auto_release(ctx: Context<SettleOrder>) -> Result<()> {
+        let order = &ctx.accounts.order;
+        let now = Clock::get()?.unix_timestamp;
+        require!(order.status == OrderStatus::Shipped, ErrorCode::InvalidOrderStatus);
+        require!(order.auto_release_due(now), ErrorCode::WindowNotExpired);
+
+        let fee = release_funds(&ctx)?;
+
+        let order = &mut ctx.accounts.order;
+        order.status = OrderStatus::AutoReleased;
+        order.confirmed_at = now;
+
+        emit!(OrderReleased {
+            order_id: order.order_id,
+            seller: order.seller,
+            amount: order.amount,
+            platform_fee: fee,
+            status: order.status,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Resolve a dispute (admin only)
+    pub fn resolve_dispute(ctx: Context<SettleOrder>, buyer_wins: bool) -> Result<()> {
+        let order = &ctx.accounts.order;
+        require!(
+            ctx.accounts.caller.key() == ctx.accounts.config.authority,
+            ErrorCode::NotAuthorized
+        );
+        require!(order.status == OrderStatus::Disputed, ErrorCode::InvalidOrderStatus);
+
+        let now = Clock::get()?.unix_timestamp;
+        if buyer_wins {
+            refund_buyer(&ctx)?;
+            ctx.accounts.order.status = OrderStatus::Refunded;
+        } else {
+            release_funds(&ctx)?;
+            let order = &mut ctx.accounts.order;
+            order.status = OrderStatus::Confirmed;
+            order.confirmed_at = now;
+        }
+
+        emit!(DisputeResolved {
+            order_id: ctx.accounts.order.order_id,
+            buyer_wins,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel an order before it ships and refund the buyer (buyer, seller or admin)
+    pub fn cancel_order(ctx: Context<SettleOrder>) -> Result<()> {
+        let order = &ctx.accounts.order;
+        let caller = ctx.accounts.caller.key();
+        require!(
+            caller == order.buyer || caller == order.seller || caller == ctx.accounts.config.authority,
+            ErrorCode::NotAuthorized
+        );
+        require!(order.status == OrderStatus::Paid, ErrorCode::InvalidOrderStatus);
+
+        refund_buyer(&ctx)?;
+        ctx.accounts.order.status = OrderStatus::Cancelled;
+
+        emit!(OrderRefunded {
+            order_id: ctx.accounts.order.order_id,
+            buyer: ctx.accounts.order.buyer,
+            amount: ctx.accounts.order.amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Accept a payment mint for new listings (admin only)
+    pub fn add_accepted_mint(ctx: Context<AddAcceptedMint>) -> Result<()> {
+        ctx.accounts.accepted_mint.mint = ctx.accounts.mint.key();
+        Ok(())
+    }
+
+    /// Stop accepting a payment mint for new listings (admin only)
+    pub fn remove_accepted_mint(_ctx: Context<RemoveAcceptedMint>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Update platform fee (admin only)
+    pub fn set_fee(ctx: Context<AdminAction>, new_fee: u16) -> Result<()> {
+        require!(new_fee <= 100, ErrorCode::FeeTooHigh); // Max 1%
+        let config = &mut ctx.accounts.config;
+        config.platform_fee = new_fee;
+        Ok(())
+    }
+
+    /// Update confirmation window (admin only)
+    pub fn set_confirmation_window(ctx: Context<AdminAction>, confirmation_window: i64) -> Result<()> {
+        require!(
+            (MIN_CONFIRMATION_WINDOW..=MAX_CONFIRMATION_WINDOW).contains(&confirmation_window),
+            ErrorCode::InvalidConfirmationWindow
+        );
+        let config = &mut ctx.accounts.config;
+        config.confirmation_window = confirmation_window;
+        Ok(())
+    }
+
+    /// Pause/unpause escrow (admin only)
+    pub fn set_paused(ctx: Context<AdminAction>, paused: bool) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.paused = paused;
+        Ok(())
+    }
+}
+
+// ============ Helpers ============
+
+/// Pay the seller from escrow, less the platform fee; returns the fee
+fn release_funds(ctx: &Context<SettleOrder>) -> Result<u64> {
+    let order = &ctx.accounts.order;
+    let (fee, seller_amount) = order.release_amounts(ctx.accounts.config.platform_fee);
+
+    let order_id = order.order_id.to_le_bytes();
+    let seeds = &[b"order".as_ref(), order_id.as_ref(), &[ctx.bumps.order]];
+    let signer = &[&seeds[..]];
+
+    if fee > 0 {
+        let fee_accounts = TokenTransfer {
+            from: ctx.accounts.escrow_payment_account.to_account_info(),
+            to: ctx.accounts.fee_collector_account.to_account_info(),
+            authority: order.to_account_info(),
+        };
+        let fee_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            fee_accounts,
+            signer,
+        );
+        token::transfer(fee_ctx, fee)?;
+    }
+
+    let seller_accounts = TokenTransfer {
+        from: ctx.accounts.escrow_payment_account.to_account_info(),
+        to: ctx.accounts.seller_payment_account.to_account_info(),
+        authority: order.to_account_info(),
+    };
+    let seller_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        seller_accounts,
+        signer,
+    );
+    token::transfer(seller_ctx, seller_amount)?;
+
+    Ok(fee)
+}
+
+/// Return the full escrowed amount to the buyer
+fn refund_buyer(ctx: &Context<SettleOrder>) -> Result<()> {
+    let order = &ctx.accounts.order;
+    let order_id = order.order_id.to_le_bytes();
+    let seeds = &[b"order".as_ref(), order_id.as_ref(), &[ctx.bumps.order]];
+    let signer = &[&seeds[..]];
+
+    let refund_accounts = TokenTransfer {
+        from: ctx.accounts.escrow_payment_account.to_account_info(),
+        to: ctx.accounts.buyer_payment_account.to_account_info(),
+        authority: order.to_account_info(),
+    };
+    let refund_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        refund_accounts,
+        signer,
+    );
+    token::transfer(refund_ctx, order.refund_amount())
+}
+
+// ============ Enums ============
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ItemType {
+    ConcertTicket,
+    VipPackage,
+    Vinyl,
+    Cd,
+    Clothing,
+    Merch,
+    Vehicle,
+    Property,
+    DigitalDownload,
+    MeetAndGreet,
+    Other,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum OrderStatus {
+    Paid,         // Waiting for seller to ship
+    Shipped,      // Seller marked as shipped
+    Confirmed,    // Buyer confirmed receipt, funds released
+    Disputed,     // Buyer filed a dispute
+    Refunded,     // Dispute resolved in buyer's favor
+    AutoReleased, // Released after the confirmation window
+    Cancelled,    // Cancelled before shipping
+}
+
+// ============ Accounts ============
+
+#[account]
+pub struct EscrowConfig {
+    pub authority: Pubkey,
+    pub platform_fee: u16,
+    pub confirmation_window: i64,
+    pub next_listing_id: u64,
+    pub next_order_id: u64,
+    pub paused: bool,
+}
+
+#[account]
+pub struct AcceptedMint {
+    pub mint: Pubkey,
+}
+
+#[account]
+pub struct ItemListing {
+    pub listing_id: u64,
+    pub seller: Pubkey,
+    pub item_type: ItemType,
+    pub title: String,        // Max 64 chars
+    pub metadata_uri: String, // Max 200 chars (description, images, shipping terms)
+    pub price: u64,
+    pub payment_mint: Pubkey,
+    pub quantity: u32,
+    pub sold: u32,
+    pub requires_shipping: bool,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub active: bool,
+}
+
+#[account]
+pub struct Order {
+    pub order_id: u64,
+    pub listing_id: u64,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub payment_mint: Pubkey,
+    pub amount: u64,
+    pub status: OrderStatus,
+    pub shipping_address_hash: [u8; 32],
+    pub tracking_info: String,  // Max 64 chars
+    pub paid_at: i64,
+    pub shipped_at: i64,
+    pub confirmed_at: i64,
+    pub disputed_at: i64,
+    pub dispute_reason: String, // Max 200 chars
+    pub auto_release_at: i64,
+}
+
+impl Order {
+    /// Platform fee and seller payout, in that order, when the escrow is released
+    pub fn release_amounts(&self, platform_fee: u16) -> (u64, u64) {
+        let fee = (self.amount as u128 * platform_fee as u128 / 10000) as u64;
+        (fee, self.amount - fee)
+    }
+
+    /// Amount returned to the buyer on a refund or cancellation; no fee is taken
+    pub fn refund_amount(&self) -> u64 {
+        self.amount
+    }
+
+    /// Whether the confirmation window since shipping has passed at `now`
+    pub fn auto_release_due(&self, now: i64) -> bool {
+        self.auto_release_at > 0 && now >= self.auto_release_at
+    }
+}
+
+// ============ Contexts ============
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 2 + 8 + 8 + 8 + 1,
+        seeds = [b"escrow_config"],
+        bump
+    )]
+    pub config: Account<'info, EscrowConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateListing<'info> {
+    #[account(mut, seeds = [b"escrow_config"], bump)]
+    pub config: Account<'info, EscrowConfig>,
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + 8 + 32 + 1 + 68 + 204 + 8 + 32 + 4 + 4 + 1 + 8 + 8 + 1,
+        seeds = [b"item_listing", config.next_listing_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, ItemListing>,
+    pub payment_mint: Account<'info, Mint>,
+    #[account(seeds = [b"accepted_mint", payment_mint.key().as_ref()], bump)]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelListing<'info> {
+    #[account(seeds = [b"escrow_config"], bump)]
+    pub config: Account<'info, EscrowConfig>,
+    #[account(
+        mut,
+        seeds = [b"item_listing", listing.listing_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, ItemListing>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Purchase<'info> {
+    #[account(mut, seeds = [b"escrow_config"], bump)]
+    pub config: Account<'info, EscrowConfig>,
+    #[account(
+        mut,
+        seeds = [b"item_listing", listing.listing_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, ItemListing>,
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + 8 + 8 + 32 + 32 + 32 + 8 + 1 + 32 + 68 + 8 + 8 + 8 + 8 + 204 + 8,
+        seeds = [b"order", config.next_order_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub order: Account<'info, Order>,
+    #[account(address = listing.payment_mint)]
+    pub payment_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(mut, token::mint = payment_mint)]
+    pub buyer_payment_account: Account<'info, TokenAccount>,
+    /// May already exist: the order address is predictable, so anyone can create its ATA first
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = payment_mint,
+        associated_token::authority = order
+    )]
+    pub escrow_payment_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct MarkShipped<'info> {
+    #[account(seeds = [b"escrow_config"], bump)]
+    pub config: Account<'info, EscrowConfig>,
+    #[account(
+        mut,
+        seeds = [b"order", order.order_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = seller
+    )]
+    pub order: Account<'info, Order>,
+    pub seller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Dispute<'info> {
+    #[account(
+        mut,
+        seeds = [b"order", order.order_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = buyer
+    )]
+    pub order: Account<'info, Order>,
+    pub buyer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SettleOrder<'info> {
+    #[account(seeds = [b"escrow_config"], bump)]
+    pub config: Account<'info, EscrowConfig>,
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+        seeds::program = soundchain_marketplace::ID
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(
+        mut,
+        seeds = [b"order", order.order_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub order: Account<'info, Order>,
+    #[account(
+        mut,
+        associated_token::mint = order.payment_mint,
+        associated_token::authority = order
+    )]
+    pub escrow_payment_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = order.payment_mint,
+        token::authority = order.seller
+    )]
+    pub seller_payment_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = order.payment_mint,
+        token::authority = order.buyer
+    )]
+    pub buyer_payment_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = order.payment_mint,
+        associated_token::authority = marketplace.fee_collector
+    )]
+    pub fee_collector_account: Account<'info, TokenAccount>,
+    pub caller: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct AddAcceptedMint<'info> {
+    #[account(seeds = [b"escrow_config"], bump, has_one = authority)]
+    pub config: Account<'info, EscrowConfig>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32,
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveAcceptedMint<'info> {
+    #[account(seeds = [b"escrow_config"], bump, has_one = authority)]
+    pub config: Account<'info, EscrowConfig>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"accepted_mint", accepted_mint.mint.as_ref()],
+        bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdminAction<'info> {
+    #[account(mut, seeds = [b"escrow_config"], bump, has_one = authority)]
+    pub config: Account<'info, EscrowConfig>,
+    pub authority: Signer<'info>,
+}
+
+// ============ Events ============
+
+#[event]
+pub struct ItemListed {
+    pub listing_id: u64,
+    pub seller: Pubkey,
+    pub item_type: ItemType,
+    pub price: u64,
+    pub payment_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ItemListingCancelled {
+    pub listing_id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrderCreated {
+    pub order_id: u64,
+    pub listing_id: u64,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub payment_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrderShipped {
+    pub order_id: u64,
+    pub tracking_info: String,
+    pub auto_release_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrderDisputed {
+    pub order_id: u64,
+    pub reason: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrderReleased {
+    pub order_id: u64,
+    pub seller: Pubkey,
+    pub amount: u64,
+    pub platform_fee: u64,
+    pub status: OrderStatus,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub order_id: u64,
+    pub buyer_wins: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrderRefunded {
+    pub order_id: u64,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+// ============ Errors ============
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Escrow is paused")]
+    EscrowPaused,
+    #[msg("Fee too high")]
+    FeeTooHigh,
+    #[msg("Confirmation window must be between 3 and 90 days")]
+    InvalidConfirmationWindow,
+    #[msg("Invalid price")]
+    InvalidPrice,
+    #[msg("Invalid quantity")]
+    InvalidQuantity,
+    #[msg("Invalid duration")]
+    InvalidDuration,
+    #[msg("Title is required and must be at most 64 chars")]
+    InvalidTitle,
+    #[msg("Metadata URI too long")]
+    UriTooLong,
+    #[msg("Tracking info too long")]
+    TrackingInfoTooLong,
+    #[msg("Dispute reason is required")]
+    ReasonRequired,
+    #[msg("Dispute reason too long")]
+    ReasonTooLong,
+    #[msg("Listing is not active")]
+    ListingNotActive,
+    #[msg("Listing has expired")]
+    ListingExpired,
+    #[msg("Sold out")]
+    SoldOut,
+    #[msg("Cannot buy own listing")]
+    CannotBuyOwnListing,
+    #[msg("Invalid order status")]
+    InvalidOrderStatus,
+    #[msg("Confirmation window has not expired")]
+    WindowNotExpired,
+    #[msg("Not the buyer")]
+    NotBuyer,
+    #[msg("Not authorized")]
+    NotAuthorized,
+}
@@ -0,0 +1,65 @@
+//! Release, refund and fee amounts for escrowed orders
+
+use anchor_lang::prelude::Pubkey;
+use soundchain_escrow::{Order, OrderStatus, MIN_CONFIRMATION_WINDOW};
+
+const SHIPPED_AT: i64 = 1_700_000_000;
+
+fn order(amount: u64) -> Order {
+    Order {
+        order_id: 1,
+        listing_id: 1,
+        buyer: Pubkey::new_unique(),
+        seller: Pubkey::new_unique(),
+        payment_mint: Pubkey::new_unique(),
+        amount,
+        status: OrderStatus::Shipped,
+        shipping_address_hash: [0; 32],
+        tracking_info: String::new(),
+        paid_at: SHIPPED_AT - 3_600,
+        shipped_at: SHIPPED_AT,
+        confirmed_at: 0,
+        disputed_at: 0,
+        dispute_reason: String::new(),
+        auto_release_at: SHIPPED_AT + MIN_CONFIRMATION_WINDOW,
+    }
+}
+
+#[test]
+fn release_splits_the_platform_fee_from_the_seller_payout() {
+    // 1% of 250 USDC
+    assert_eq!(order(250_000_000).release_amounts(100), (2_500_000, 247_500_000));
+    // 0.05%, rounded down in the seller's favor
+    assert_eq!(order(1_999).release_amounts(5), (0, 1_999));
+    assert_eq!(order(20_000).release_amounts(5), (10, 19_990));
+    // No fee configured
+    assert_eq!(order(20_000).release_amounts(0), (0, 20_000));
+}
+
+#[test]
+fn release_amounts_always_add_up_to_the_escrow() {
+    for amount in [1, 99, 10_001, 123_456_789, u64::MAX] {
+        for platform_fee in [0, 1, 5, 50, 100] {
+            let (fee, seller_amount) = order(amount).release_amounts(platform_fee);
+            assert_eq!(fee + seller_amount, amount);
+            assert!(fee <= amount / 100);
+        }
+    }
+}
+
+#[test]
+fn refunds_return_the_full_escrow() {
+    assert_eq!(order(250_000_000).refund_amount(), 250_000_000);
+}
+
+#[test]
+fn auto_release_waits_for_the_confirmation_window() {
+    let shipped = order(250_000_000);
+    assert!(!shipped.auto_release_due(SHIPPED_AT));
+    assert!(!shipped.auto_release_due(SHIPPED_AT + MIN_CONFIRMATION_WINDOW - 1));
+    assert!(shipped.auto_release_due(SHIPPED_AT + MIN_CONFIRMATION_WINDOW));
+
+    // Disputes clear the release time
+    let disputed = Order { auto_release_at: 0, status: OrderStatus::Disputed, ..shipped };
+    assert!(!disputed.auto_release_due(i64::MAX));
+}
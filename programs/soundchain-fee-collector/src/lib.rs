@@ -0,0 +1,595 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer as TokenTransfer};
+use anchor_spl::associated_token::{get_associated_token_address, AssociatedToken};
+
+declare_id!("B4tcva3LPMpSUvR9uyKmHA9kJMNSfhn755DKEGf8BSeh");
+
+/// Max length of the transaction type tag passed by collectors
+pub const MAX_TRANSACTION_TYPE_LEN: usize = 32;
+
+/// SoundChain Fee Collector for Solana
+///
+/// Collects platform fees and routes them to the treasury,
+/// along the lines of SoundchainFeeCollector.sol.
+///
+/// The collector config PDA owns one fee vault (its ATA) per payment mint,
+/// so programs can point their `fee_collector` at the config PDA and pay
+/// fees to `ATA(payment_mint, fee_collector)`. Each mint has a FeeVault PDA
+/// tracking pending and total collected fees for reconciliation.
+/// Lamport fees (e.g. SCid registration) are sent to the config PDA directly.
+#[program]
+pub mod soundchain_fee_collector {
+    use super::*;
+
+    /// Initialize the fee collector
+    pub fn initialize(ctx: Context<Initialize>, treasury: Pubkey) -> Result<()> {
+        require!(treasury != Pubkey::default(), ErrorCode::InvalidTreasury);
+
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.treasury = treasury;
+        config.total_native_withdrawn = 0;
+
+        emit!(TreasuryUpdated {
+            old_treasury: Pubkey::default(),
+            new_treasury: treasury,
+        });
+
+        Ok(())
+    }
+
+    /// Create the fee vault for a payment mint (admin only)
+    pub fn create_fee_vault(ctx: Context<CreateFeeVault>) -> Result<()> {
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        fee_vault.mint = ctx.accounts.mint.key();
+        fee_vault.vault = ctx.accounts.vault.key();
+        fee_vault.pending_fees = 0;
+        fee_vault.total_collected = 0;
+        fee_vault.total_withdrawn = 0;
+        Ok(())
+    }
+
+    /// Collect a fee from a payer (authorized collectors only, usually via CPI)
+    pub fn collect_fee(
+        ctx: Context<CollectFee>,
+        amount: u64,
+        transaction_type: String,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::ZeroAmount);
+        require!(
+            transaction_type.len() <= MAX_TRANSACTION_TYPE_LEN,
+            ErrorCode::TransactionTypeTooLong
+        );
+
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.payer_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.payer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        fee_vault.record(amount)?;
+
+        emit!(FeeCollected {
+            mint: fee_vault.mint,
+            amount,
+            from: ctx.accounts.payer.key(),
+            collector: ctx.accounts.collector_authority.key(),
+            transaction_type,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Record fees that were transferred straight into a vault
+    pub fn sync_fees(ctx: Context<SyncFees>) -> Result<()> {
+        let untracked = ctx.accounts.fee_vault.sync(ctx.accounts.vault.amount)?;
+        if untracked > 0 {
+            emit!(FeesSynced {
+                mint: ctx.accounts.fee_vault.mint,
+                amount: untracked,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        }
+        Ok(())
+    }
+
+    /// Withdraw pending fees for a mint to the treasury
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>) -> Result<()> {
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        fee_vault.sync(ctx.accounts.vault.amount)?;
+
+        let amount = fee_vault.pending_fees;
+        require!(amount > 0, ErrorCode::NoPendingFees);
+        fee_vault.pending_fees = 0;
+        fee_vault.total_withdrawn += amount;
+
+        let seeds = &[b"fee_collector".as_ref(), &[ctx.bumps.config]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.treasury_token_account.to_account_info(),
+            authority: ctx.accounts.config.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(FeeWithdrawn {
+            mint: ctx.accounts.fee_vault.mint,
+            amount,
+            to: ctx.accounts.treasury_token_account.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw pending fees for several mints to the treasury
+    ///
+    /// Remaining accounts are triples of
+    /// `[fee_vault (mut), vault (mut), treasury_token_account (mut)]`.
+    /// Mints with nothing pending are skipped.
+    pub fn withdraw_all_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawAllFees<'info>>,
+    ) -> Result<()> {
+        let remaining = ctx.remaining_accounts;
+        require!(
+            !remaining.is_empty() && remaining.len() % 3 == 0,
+            ErrorCode::InvalidRemainingAccounts
+        );
+
+        let config_key = ctx.accounts.config.key();
+        let treasury = ctx.accounts.config.treasury;
+        let seeds = &[b"fee_collector".as_ref(), &[ctx.bumps.config]];
+        let signer = &[&seeds[..]];
+
+        for chunk in remaining.chunks(3) {
+            let mut fee_vault: Account<FeeVault> = Account::try_from(&chunk[0])?;
+            let vault: Account<TokenAccount> = Account::try_from(&chunk[1])?;
+            let treasury_token_account: Account<TokenAccount> = Account::try_from(&chunk[2])?;
+
+            let (expected_fee_vault, _) = Pubkey::find_program_address(
+                &[b"fee_vault", fee_vault.mint.as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(fee_vault.key(), expected_fee_vault, ErrorCode::InvalidFeeVault);
+            require_keys_eq!(
+                vault.key(),
+                get_associated_token_address(&config_key, &fee_vault.mint),
+                ErrorCode::InvalidFeeVault
+            );
+            require_keys_eq!(treasury_token_account.mint, fee_vault.mint, ErrorCode::InvalidTreasury);
+            require_keys_eq!(treasury_token_account.owner, treasury, ErrorCode::InvalidTreasury);
+
+            fee_vault.sync(vault.amount)?;
+            let amount = fee_vault.pending_fees;
+            if amount == 0 {
+                continue;
+            }
+            fee_vault.pending_fees = 0;
+            fee_vault.total_withdrawn += amount;
+
+            let cpi_accounts = TokenTransfer {
+                from: vault.to_account_info(),
+                to: treasury_token_account.to_account_info(),
+                authority: ctx.accounts.config.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token::transfer(cpi_ctx, amount)?;
+            fee_vault.exit(ctx.program_id)?;
+
+            emit!(FeeWithdrawn {
+                mint: fee_vault.mint,
+                amount,
+                to: treasury_token_account.key(),
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Withdraw lamport fees held by the config PDA to the treasury
+    pub fn withdraw_native_fees(ctx: Context<WithdrawNativeFees>) -> Result<()> {
+        let config_info = ctx.accounts.config.to_account_info();
+        let rent_exempt = Rent::get()?.minimum_balance(config_info.data_len());
+        let amount = config_info.lamports().saturating_sub(rent_exempt);
+        require!(amount > 0, ErrorCode::NoPendingFees);
+
+        **config_info.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.treasury.try_borrow_mut_lamports()? += amount;
+        ctx.accounts.config.total_native_withdrawn += amount;
+
+        emit!(FeeWithdrawn {
+            mint: Pubkey::default(),
+            amount,
+            to: ctx.accounts.treasury.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Authorize a collector signer (admin only)
+    ///
+    /// Collector programs sign `collect_fee` with one of their PDAs;
+    /// that PDA is the key authorized here.
+    pub fn authorize_collector(ctx: Context<AuthorizeCollector>, collector: Pubkey) -> Result<()> {
+        let authorization = &mut ctx.accounts.authorization;
+        authorization.collector = collector;
+
+        emit!(AuthorizedCollectorUpdated {
+            collector,
+            authorized: true,
+        });
+
+        Ok(())
+    }
+
+    /// Revoke a collector signer (admin only)
+    pub fn revoke_collector(ctx: Context<RevokeCollector>) -> Result<()> {
+        emit!(AuthorizedCollectorUpdated {
+            collector: ctx.accounts.authorization.collector,
+            authorized: false,
+        });
+
+        Ok(())
+    }
+
+    /// Update the treasury (admin only)
+    pub fn set_treasury(ctx: Context<AdminAction>, new_treasury: Pubkey) -> Result<()> {
+        require!(new_treasury != Pubkey::default(), ErrorCode::InvalidTreasury);
+
+        let config = &mut ctx.accounts.config;
+        let old_treasury = config.treasury;
+        config.treasury = new_treasury;
+
+        emit!(TreasuryUpdated {
+            old_treasury,
+            new_treasury,
+        });
+
+        Ok(())
+    }
+
+    /// Emergency withdrawal to any token account (admin only)
+    pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::ZeroAmount);
+        ctx.accounts.fee_vault.sync(ctx.accounts.vault.amount)?;
+
+        let seeds = &[b"fee_collector".as_ref(), &[ctx.bumps.config]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.config.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        fee_vault.pending_fees = fee_vault.pending_fees.saturating_sub(amount);
+        fee_vault.total_withdrawn += amount;
+
+        emit!(EmergencyWithdrawn {
+            mint: fee_vault.mint,
+            amount,
+            to: ctx.accounts.destination.key(),
+            by: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+// ============ Accounts ============
+
+#[account]
+pub struct FeeCollector {
+    pub authority: Pubkey,
+    pub treasury: Pubkey,
+    pub total_native_withdrawn: u64,
+}
+
+#[account]
+pub struct FeeVault {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub pending_fees: u64,
+    pub total_collected: u64,
+    pub total_withdrawn: u64,
+}
+
+impl FeeVault {
+    /// Record a tracked fee deposit
+    pub fn record(&mut self, amount: u64) -> Result<()> {
+        self.pending_fees = self
+            .pending_fees
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_collected = self
+            .total_collected
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Record any vault balance not yet tracked as pending; returns the untracked amount
+    pub fn sync(&mut self, vault_balance: u64) -> Result<u64> {
+        let untracked = vault_balance.saturating_sub(self.pending_fees);
+        if untracked > 0 {
+            self.record(untracked)?;
+        }
+        Ok(untracked)
+    }
+}
+
+#[account]
+pub struct AuthorizedCollector {
+    pub collector: Pubkey,
+}
+
+// ============ Contexts ============
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 8,
+        seeds = [b"fee_collector"],
+        bump
+    )]
+    pub config: Account<'info, FeeCollector>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateFeeVault<'info> {
+    #[account(seeds = [b"fee_collector"], bump, has_one = authority)]
+    pub config: Account<'info, FeeCollector>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 8 + 8,
+        seeds = [b"fee_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+    pub mint: Account<'info, Mint>,
+    /// May already hold fees: marketplace buyers create this ATA when paying
+    /// before the vault is registered. Those fees are picked up by `sync`.
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = config
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CollectFee<'info> {
+    #[account(
+        mut,
+        seeds = [b"fee_vault", fee_vault.mint.as_ref()],
+        bump,
+        has_one = vault
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [b"collector", collector_authority.key().as_ref()],
+        bump
+    )]
+    pub authorization: Account<'info, AuthorizedCollector>,
+    pub collector_authority: Signer<'info>,
+    pub payer: Signer<'info>,
+    #[account(mut, token::mint = fee_vault.mint)]
+    pub payer_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SyncFees<'info> {
+    #[account(
+        mut,
+        seeds = [b"fee_vault", fee_vault.mint.as_ref()],
+        bump,
+        has_one = vault
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+    pub vault: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(seeds = [b"fee_collector"], bump)]
+    pub config: Account<'info, FeeCollector>,
+    #[account(
+        mut,
+        seeds = [b"fee_vault", fee_vault.mint.as_ref()],
+        bump,
+        has_one = vault
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = fee_vault.mint,
+        token::authority = config.treasury
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawAllFees<'info> {
+    #[account(seeds = [b"fee_collector"], bump)]
+    pub config: Account<'info, FeeCollector>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawNativeFees<'info> {
+    #[account(mut, seeds = [b"fee_collector"], bump, has_one = treasury)]
+    pub config: Account<'info, FeeCollector>,
+    /// CHECK: Treasury wallet, checked against config.treasury
+    #[account(mut)]
+    pub treasury: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(collector: Pubkey)]
+pub struct AuthorizeCollector<'info> {
+    #[account(seeds = [b"fee_collector"], bump, has_one = authority)]
+    pub config: Account<'info, FeeCollector>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32,
+        seeds = [b"collector", collector.as_ref()],
+        bump
+    )]
+    pub authorization: Account<'info, AuthorizedCollector>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeCollector<'info> {
+    #[account(seeds = [b"fee_collector"], bump, has_one = authority)]
+    pub config: Account<'info, FeeCollector>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"collector", authorization.collector.as_ref()],
+        bump
+    )]
+    pub authorization: Account<'info, AuthorizedCollector>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdminAction<'info> {
+    #[account(mut, seeds = [b"fee_collector"], bump, has_one = authority)]
+    pub config: Account<'info, FeeCollector>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    #[account(seeds = [b"fee_collector"], bump, has_one = authority)]
+    pub config: Account<'info, FeeCollector>,
+    #[account(
+        mut,
+        seeds = [b"fee_vault", fee_vault.mint.as_ref()],
+        bump,
+        has_one = vault
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    #[account(mut, token::mint = fee_vault.mint)]
+    pub destination: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+// ============ Events ============
+
+#[event]
+pub struct FeeCollected {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub from: Pubkey,
+    pub collector: Pubkey,
+    pub transaction_type: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesSynced {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeWithdrawn {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub to: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmergencyWithdrawn {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub to: Pubkey,
+    pub by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryUpdated {
+    pub old_treasury: Pubkey,
+    pub new_treasury: Pubkey,
+}
+
+#[event]
+pub struct AuthorizedCollectorUpdated {
+    pub collector: Pubkey,
+    pub authorized: bool,
+}
+
+// ============ Errors ============
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Amount must be greater than 0")]
+    ZeroAmount,
+    #[msg("Transaction type too long")]
+    TransactionTypeTooLong,
+    #[msg("No pending fees")]
+    NoPendingFees,
+    #[msg("Invalid treasury")]
+    InvalidTreasury,
+    #[msg("Invalid fee vault")]
+    InvalidFeeVault,
+    #[msg("Remaining accounts must be [fee_vault, vault, treasury_token_account] triples")]
+    InvalidRemainingAccounts,
+    #[msg("Math overflow")]
+    MathOverflow,
+}
@@ -1,8 +1,62 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, keccak, program::invoke};
 use anchor_spl::token::{self, Token, TokenAccount, Transfer as TokenTransfer};
-use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::associated_token::{get_associated_token_address, AssociatedToken};
+use soundchain_staking::StakeAccount;
 
-declare_id!("SMktxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx");
+declare_id!("FGfzcF4WWgPyjcAcMGnXoUDb9sbLxqTof7X22PUZiNES");
+
+/// Max listings bought in a single sweep
+pub const MAX_SWEEP_SIZE: usize = 10;
+
+/// Remaining accounts per listing in a sweep
+pub const SWEEP_ACCOUNTS_PER_LISTING: usize = 9;
+
+/// Max payment mints a listing accepts besides its own `payment_mint`
+pub const MAX_EXTRA_PAYMENT_MINTS: usize = 8;
+
+/// Max platform fee (10%)
+pub const MAX_PLATFORM_FEE: u16 = 1000;
+
+/// Current account layout versions; accounts created before versioning read as 0
+pub const MARKETPLACE_VERSION: u8 = 1;
+pub const LISTING_VERSION: u8 = 4;
+pub const AUCTION_VERSION: u8 = 1;
+
+/// Account sizes for the current layouts
+pub const MARKETPLACE_SPACE: usize = 8 + 32 + 32 + 2 + 8 + 8 + 1 + 32 + 32 + 2 + 8 + 33 + 3 + 8 + 8 + 2 + 1;
+pub const LISTING_SPACE: usize = 8 + 32 + 32 + 32 + 8 + 1 + 1 + 8 + 8 + 36 + 33 + 9 + 1 + 9 + 34 + 42;
+pub const AUCTION_SPACE: usize = 8 + 32 + 8 + 32 + 4 + 1 + 33 + 1;
+pub const SALES_STATS_SPACE: usize = 8 + 32 + 32 + 8 + 8 + 16 + 8 + 8 + 8;
+pub const PRICE_FEED_SPACE: usize = 8 + 32 + 32 + 1 + 8 + 2 + 32;
+pub const LISTING_PRICES_SPACE: usize = 8 + 32 + 4 + MAX_EXTRA_PAYMENT_MINTS * (32 + 8);
+
+/// Metaplex Token Metadata program
+pub mod token_metadata {
+    use super::*;
+    declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+}
+
+/// Pyth Solana receiver program, owner of `PriceUpdateV2` accounts
+pub mod pyth_receiver {
+    use super::*;
+    declare_id!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+}
+
+/// USD listing prices are in micro-dollars
+pub const USD_DECIMALS: u8 = 6;
+
+/// Discriminator of a Pyth receiver `PriceUpdateV2` account
+pub const PYTH_PRICE_UPDATE_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+/// Instruction discriminator a `buy_with_swap` swap program must accept,
+/// Anchor's `global:swap`, followed by `amount_in: u64, min_amount_out: u64`
+pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+
+/// Fee change timelock bounds (24 hours - 30 days), 48 hours by default
+pub const DEFAULT_FEE_DELAY: i64 = 172_800;
+pub const MIN_FEE_DELAY: i64 = 86_400;
+pub const MAX_FEE_DELAY: i64 = 2_592_000;
 
 /// SoundChain Marketplace for Solana
 ///
@@ -24,6 +78,7 @@ pub mod soundchain_marketplace {
         fee_collector: Pubkey,
         platform_fee: u16,
     ) -> Result<()> {
+        require!(platform_fee <= MAX_PLATFORM_FEE, ErrorCode::FeeTooHigh);
         let marketplace = &mut ctx.accounts.marketplace;
         marketplace.authority = ctx.accounts.authority.key();
         marketplace.fee_collector = fee_collector;
@@ -31,6 +86,16 @@ pub mod soundchain_marketplace {
         marketplace.total_listings = 0;
         marketplace.total_sales = 0;
         marketplace.paused = false;
+        marketplace.rewards_mint = Pubkey::default();
+        marketplace.rewards_vault = Pubkey::default();
+        marketplace.rewards_rate = 0;
+        marketplace.rewards_limit = 0;
+        marketplace.pending_authority = None;
+        marketplace.pending_fee = None;
+        marketplace.fee_change_eta = 0;
+        marketplace.fee_delay = DEFAULT_FEE_DELAY;
+        marketplace.referral_share = 0;
+        marketplace.version = MARKETPLACE_VERSION;
         Ok(())
     }
 
@@ -41,41 +106,20 @@ pub mod soundchain_marketplace {
         duration: i64,
         scid: Option<String>,
     ) -> Result<()> {
-        let marketplace = &ctx.accounts.marketplace;
-        require!(!marketplace.paused, ErrorCode::MarketplacePaused);
-
-        let listing = &mut ctx.accounts.listing;
-        listing.seller = ctx.accounts.seller.key();
-        listing.nft_mint = ctx.accounts.nft_mint.key();
-        listing.payment_mint = ctx.accounts.payment_mint.key();
-        listing.price = price;
-        listing.listing_type = ListingType::FixedPrice;
-        listing.status = ListingStatus::Active;
-        listing.created_at = Clock::get()?.unix_timestamp;
-        listing.expires_at = Clock::get()?.unix_timestamp + duration;
-        listing.scid = scid;
-        listing.buyer = None;
-        listing.sold_at = None;
-
-        // Transfer NFT to escrow
-        let cpi_accounts = TokenTransfer {
-            from: ctx.accounts.seller_nft_account.to_account_info(),
-            to: ctx.accounts.escrow_nft_account.to_account_info(),
-            authority: ctx.accounts.seller.to_account_info(),
-        };
-        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-        token::transfer(cpi_ctx, 1)?;
-
-        emit!(ListingCreated {
-            listing: listing.key(),
-            seller: listing.seller,
-            nft_mint: listing.nft_mint,
-            price,
-            listing_type: ListingType::FixedPrice,
-            timestamp: listing.created_at,
-        });
+        list_fixed_price(ctx, price, None, duration, scid)
+    }
 
-        Ok(())
+    /// Create a fixed price listing priced in USD micro-dollars; buyers pay
+    /// in any mint with a price feed at its oracle rate through `buy_usd`
+    pub fn create_usd_listing(
+        ctx: Context<CreateListing>,
+        usd_price: u64,
+        duration: i64,
+        scid: Option<String>,
+    ) -> Result<()> {
+        require!(usd_price > 0, ErrorCode::InvalidUsdPrice);
+        require!(ctx.accounts.price_feed.is_some(), ErrorCode::PriceFeedRequired);
+        list_fixed_price(ctx, 0, Some(usd_price), duration, scid)
     }
 
     /// Create an auction listing
@@ -90,6 +134,8 @@ pub mod soundchain_marketplace {
         require!(duration >= 3600, ErrorCode::DurationTooShort); // Min 1 hour
         require!(duration <= 2592000, ErrorCode::DurationTooLong); // Max 30 days
 
+        record_listing(ctx.accounts)?;
+
         let listing = &mut ctx.accounts.listing;
         listing.seller = ctx.accounts.seller.key();
         listing.nft_mint = ctx.accounts.nft_mint.key();
@@ -102,6 +148,10 @@ pub mod soundchain_marketplace {
         listing.scid = scid;
         listing.buyer = None;
         listing.sold_at = None;
+        listing.version = LISTING_VERSION;
+        listing.usd_price = None;
+        listing.restriction = None;
+        listing.token_gate = None;
 
         // Transfer NFT to escrow
         let cpi_accounts = TokenTransfer {
@@ -124,151 +174,472 @@ pub mod soundchain_marketplace {
         Ok(())
     }
 
-    /// Buy a fixed price listing
+    /// Buy a fixed price listing, paying in the mint of `buyer_payment_account`:
+    /// the listing's `payment_mint`, or one of its `listing_prices`
     pub fn buy(ctx: Context<Buy>) -> Result<()> {
+        buy_listing(ctx, &[])
+    }
+
+    /// `buy` for listings restricted to an allowlist, with the buyer's Merkle proof
+    pub fn buy_with_proof(ctx: Context<Buy>, proof: Vec<[u8; 32]>) -> Result<()> {
+        buy_listing(ctx, &proof)
+    }
+
+    /// Buy a USD-priced listing in the mint of `buyer_payment_account` at its
+    /// oracle rate, paying at most `max_amount`
+    pub fn buy_usd(ctx: Context<Buy>, max_amount: u64) -> Result<()> {
+        let usd_price = ctx.accounts.listing.usd_price.ok_or(ErrorCode::NotUsdPriced)?;
+        let price_feed = ctx.accounts.price_feed.as_ref().ok_or(ErrorCode::PriceFeedRequired)?;
+        let oracle = ctx.accounts.price_oracle.as_ref().ok_or(ErrorCode::PriceFeedRequired)?;
+        require_keys_eq!(oracle.key(), price_feed.oracle, ErrorCode::InvalidPriceOracle);
+        require_keys_eq!(*oracle.owner, pyth_receiver::ID, ErrorCode::InvalidPriceOracle);
+
+        let update = PythPriceUpdate::decode(&oracle.try_borrow_data()?)?;
+        let amount = usd_to_tokens(usd_price, &update, price_feed, Clock::get()?.unix_timestamp)?;
+        require!(amount <= max_amount, ErrorCode::PriceAboveMax);
+
+        // Sold like a fixed price listing in the chosen mint at the converted
+        // price, which stays on the listing
+        let payment_mint = ctx.accounts.buyer_payment_account.mint;
         let listing = &mut ctx.accounts.listing;
-        let marketplace = &ctx.accounts.marketplace;
+        listing.payment_mint = payment_mint;
+        listing.price = amount;
+        execute_buy(ctx, &[])
+    }
 
-        require!(!marketplace.paused, ErrorCode::MarketplacePaused);
-        require!(listing.status == ListingStatus::Active, ErrorCode::ListingNotActive);
-        require!(listing.listing_type == ListingType::FixedPrice, ErrorCode::NotFixedPrice);
-        require!(
-            Clock::get()?.unix_timestamp < listing.expires_at,
-            ErrorCode::ListingExpired
+    /// Buy a fixed price listing with another token, swapped into the listing's
+    /// `payment_mint` first
+    ///
+    /// `swap_program` is called with `SWAP_DISCRIMINATOR, amount_in, min_amount_out`
+    /// and accounts `[buyer (signer), swap_source_account (mut), buyer_payment_account (mut), token_program]`
+    /// followed by the remaining accounts, which belong to the swap program's pool.
+    /// The swap must add at least `min_amount_out` to `buyer_payment_account`;
+    /// anything left over after the sale stays with the buyer.
+    pub fn buy_with_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Buy<'info>>,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        require!(ctx.accounts.listing.usd_price.is_none(), ErrorCode::UsdPriced);
+        require_keys_eq!(
+            ctx.accounts.buyer_payment_account.mint,
+            ctx.accounts.listing.payment_mint,
+            ErrorCode::PaymentMintNotAccepted
         );
+        let swap_program = ctx.accounts.swap_program.as_ref().ok_or(ErrorCode::SwapAccountsRequired)?;
+        let swap_source = ctx.accounts.swap_source_account.as_ref().ok_or(ErrorCode::SwapAccountsRequired)?;
+
+        let mut data = SWAP_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&amount_in.to_le_bytes());
+        data.extend_from_slice(&min_amount_out.to_le_bytes());
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(ctx.accounts.buyer.key(), true),
+            AccountMeta::new(swap_source.key(), false),
+            AccountMeta::new(ctx.accounts.buyer_payment_account.key(), false),
+            AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
+        ];
+        let mut infos = vec![
+            ctx.accounts.buyer.to_account_info(),
+            swap_source.to_account_info(),
+            ctx.accounts.buyer_payment_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+        ];
+        for account in ctx.remaining_accounts {
+            accounts.push(match account.is_writable {
+                true => AccountMeta::new(account.key(), account.is_signer),
+                false => AccountMeta::new_readonly(account.key(), account.is_signer),
+            });
+            infos.push(account.clone());
+        }
+        infos.push(swap_program.to_account_info());
+
+        let balance_before = ctx.accounts.buyer_payment_account.amount;
+        let ix = Instruction { program_id: swap_program.key(), accounts, data };
+        invoke(&ix, &infos)?;
+
+        // Only what the swap itself added counts towards the guard
+        ctx.accounts.buyer_payment_account.reload()?;
+        let amount_out = ctx
+            .accounts
+            .buyer_payment_account
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(ErrorCode::SwapOutputTooLow)?;
+        require!(amount_out >= min_amount_out, ErrorCode::SwapOutputTooLow);
+
+        emit!(SwapPayment {
+            listing: ctx.accounts.listing.key(),
+            buyer: ctx.accounts.buyer.key(),
+            swap_program: swap_program.key(),
+            source_mint: swap_source.mint,
+            amount_in,
+            amount_out,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-        // Calculate fees
-        let platform_fee = (listing.price as u128 * marketplace.platform_fee as u128 / 10000) as u64;
-        let seller_amount = listing.price - platform_fee;
+        execute_buy(ctx, &[])
+    }
 
-        // Transfer payment from buyer
-        let cpi_accounts = TokenTransfer {
-            from: ctx.accounts.buyer_payment_account.to_account_info(),
-            to: ctx.accounts.seller_payment_account.to_account_info(),
-            authority: ctx.accounts.buyer.to_account_info(),
-        };
-        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-        token::transfer(cpi_ctx, seller_amount)?;
+    /// Buy several fixed price listings in one transaction
+    ///
+    /// Remaining accounts are groups of
+    /// `[listing (mut), escrow_nft_account (mut), buyer_nft_account (mut), seller_payment_account (mut), seller_stats (mut),
+    /// nft_metadata, collection_fee, partner_fee, collection_stats (mut)]`.
+    /// Seller stats must already exist; `create_listing` and `init_seller_stats` create them.
+    /// The last four are optional, as in `buy`; pass the program id to omit one.
+    /// Fees are resolved per listing like `buy`, with the buyer's OGUN discount.
+    /// Listings must be priced in the mint of `buyer_payment_account`. In
+    /// `SkipUnavailable` mode, listings that are no longer for sale or would push
+    /// the total over `max_total_price` are skipped instead of failing the sweep.
+    pub fn sweep<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Sweep<'info>>,
+        max_total_price: u64,
+        mode: SweepMode,
+    ) -> Result<()> {
+        let marketplace = &ctx.accounts.marketplace;
+        require!(!marketplace.paused, ErrorCode::MarketplacePaused);
 
-        // Transfer platform fee
-        let fee_accounts = TokenTransfer {
-            from: ctx.accounts.buyer_payment_account.to_account_info(),
-            to: ctx.accounts.fee_collector_account.to_account_info(),
-            authority: ctx.accounts.buyer.to_account_info(),
-        };
-        let fee_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), fee_accounts);
-        token::transfer(fee_ctx, platform_fee)?;
+        let remaining = ctx.remaining_accounts;
+        require!(
+            !remaining.is_empty()
+                && remaining.len() % SWEEP_ACCOUNTS_PER_LISTING == 0
+                && remaining.len() / SWEEP_ACCOUNTS_PER_LISTING <= MAX_SWEEP_SIZE,
+            ErrorCode::InvalidSweepAccounts
+        );
 
-        // Transfer NFT to buyer (from escrow)
-        let seeds = &[
-            b"listing",
-            listing.nft_mint.as_ref(),
-            &[ctx.bumps.listing],
-        ];
-        let signer = &[&seeds[..]];
+        let now = Clock::get()?.unix_timestamp;
+        let buyer = ctx.accounts.buyer.key();
+        let payment_mint = ctx.accounts.buyer_payment_account.mint;
+        let mut total_price: u64 = 0;
+        let mut items_bought: u8 = 0;
+
+        for chunk in remaining.chunks(SWEEP_ACCOUNTS_PER_LISTING) {
+            let mut listing: Account<Listing> = Account::try_from(&chunk[0])?;
+            let escrow_nft_account: Account<TokenAccount> = Account::try_from(&chunk[1])?;
+            let buyer_nft_account: Account<TokenAccount> = Account::try_from(&chunk[2])?;
+            let seller_payment_account: Account<TokenAccount> = Account::try_from(&chunk[3])?;
+            let mut seller_stats: Account<SalesStats> = Account::try_from(&chunk[4])?;
+            let nft_metadata = sweep_optional(&chunk[5], ctx.program_id).map(UncheckedAccount::try_from);
+            let collection_fee: Option<Account<CollectionFee>> =
+                sweep_optional(&chunk[6], ctx.program_id).map(Account::try_from).transpose()?;
+            let partner_fee: Option<Account<PartnerFee>> =
+                sweep_optional(&chunk[7], ctx.program_id).map(Account::try_from).transpose()?;
+            let mut collection_stats: Option<Account<SalesStats>> =
+                sweep_optional(&chunk[8], ctx.program_id).map(Account::try_from).transpose()?;
+
+            let (expected_listing, listing_bump) = Pubkey::find_program_address(
+                &[b"listing", listing.nft_mint.as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(listing.key(), expected_listing, ErrorCode::InvalidSweepAccounts);
+            require_keys_eq!(
+                escrow_nft_account.key(),
+                get_associated_token_address(&listing.key(), &listing.nft_mint),
+                ErrorCode::InvalidSweepAccounts
+            );
+            require_keys_eq!(buyer_nft_account.mint, listing.nft_mint, ErrorCode::InvalidSweepAccounts);
+            require_keys_eq!(buyer_nft_account.owner, buyer, ErrorCode::InvalidSweepAccounts);
+            require_keys_eq!(seller_payment_account.mint, listing.payment_mint, ErrorCode::InvalidSweepAccounts);
+            require_keys_eq!(seller_payment_account.owner, listing.seller, ErrorCode::InvalidSweepAccounts);
+            let (expected_stats, _) = Pubkey::find_program_address(
+                &[b"seller_stats", listing.seller.as_ref(), listing.payment_mint.as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(seller_stats.key(), expected_stats, ErrorCode::InvalidSweepAccounts);
+            if let Some(nft_metadata) = &nft_metadata {
+                let (expected_metadata, _) = Pubkey::find_program_address(
+                    &[b"metadata", token_metadata::ID.as_ref(), listing.nft_mint.as_ref()],
+                    &token_metadata::ID,
+                );
+                require_keys_eq!(nft_metadata.key(), expected_metadata, ErrorCode::InvalidSweepAccounts);
+                require_keys_eq!(*nft_metadata.owner, token_metadata::ID, ErrorCode::InvalidSweepAccounts);
+            }
+            if let Some(collection_fee) = &collection_fee {
+                let (expected_fee, _) = Pubkey::find_program_address(
+                    &[b"collection_fee", collection_fee.collection.as_ref()],
+                    ctx.program_id,
+                );
+                require_keys_eq!(collection_fee.key(), expected_fee, ErrorCode::InvalidSweepAccounts);
+            }
+            if let Some(partner_fee) = &partner_fee {
+                let (expected_fee, _) =
+                    Pubkey::find_program_address(&[b"partner_fee", listing.seller.as_ref()], ctx.program_id);
+                require_keys_eq!(partner_fee.key(), expected_fee, ErrorCode::InvalidSweepAccounts);
+            }
+            if let Some(collection_stats) = &collection_stats {
+                let (expected_stats, _) = Pubkey::find_program_address(
+                    &[b"collection_stats", collection_stats.key.as_ref(), collection_stats.payment_mint.as_ref()],
+                    ctx.program_id,
+                );
+                require_keys_eq!(collection_stats.key(), expected_stats, ErrorCode::InvalidSweepAccounts);
+            }
+
+            let available = listing.status == ListingStatus::Active
+                && listing.listing_type == ListingType::FixedPrice
+                && listing.usd_price.is_none()
+                && listing.buyer_allowed(&buyer, &[])
+                && listing.token_gate.is_none()
+                && now < listing.expires_at
+                && listing.payment_mint == payment_mint;
+            let new_total = total_price
+                .checked_add(listing.price)
+                .filter(|total| *total <= max_total_price);
+
+            let new_total = match (available, new_total, mode) {
+                (true, Some(new_total), _) => new_total,
+                (_, _, SweepMode::SkipUnavailable) => continue,
+                (false, _, SweepMode::AllOrNothing) => return err!(ErrorCode::ListingUnavailable),
+                (true, None, SweepMode::AllOrNothing) => return err!(ErrorCode::SweepBudgetExceeded),
+            };
 
-        let nft_accounts = TokenTransfer {
-            from: ctx.accounts.escrow_nft_account.to_account_info(),
-            to: ctx.accounts.buyer_nft_account.to_account_info(),
-            authority: ctx.accounts.listing.to_account_info(),
-        };
-        let nft_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            nft_accounts,
-            signer,
-        );
-        token::transfer(nft_ctx, 1)?;
+            let fee_bps = tiered_fee_bps(
+                marketplace.platform_fee,
+                collection_fee.as_deref(),
+                nft_metadata.as_deref(),
+                partner_fee.as_deref(),
+            )?;
+            let (fee_bps, discount) = discounted_fee_bps(
+                fee_bps,
+                ctx.accounts.fee_schedule.as_deref(),
+                ctx.accounts.buyer_ogun_account.as_deref(),
+                ctx.accounts.buyer_stake_account.as_deref(),
+                buyer,
+            )?;
+
+            let platform_fee = FixedPriceSale {
+                marketplace,
+                marketplace_bump: ctx.bumps.marketplace,
+                listing: &listing,
+                listing_bump,
+                fee_bps,
+                payer: ctx.accounts.buyer.to_account_info(),
+                payer_signer: &[],
+                buyer,
+                payer_payment_account: &ctx.accounts.buyer_payment_account,
+                buyer_rewards_account: &ctx.accounts.buyer_payment_account,
+                buyer_nft_account: &buyer_nft_account,
+                seller_payment_account: &seller_payment_account,
+                fee_collector_account: &ctx.accounts.fee_collector_account,
+                escrow_nft_account: &escrow_nft_account,
+                token_program: &ctx.accounts.token_program,
+                rewards_vault: ctx.accounts.rewards_vault.as_ref(),
+                referral: None,
+            }
+            .execute()?;
+
+            emit!(FeeApplied {
+                listing: listing.key(),
+                buyer,
+                base_fee_bps: marketplace.platform_fee,
+                applied_fee_bps: fee_bps,
+                discount,
+                platform_fee,
+                timestamp: now,
+            });
+
+            mark_sold(&mut listing, buyer, platform_fee)?;
+            listing.exit(ctx.program_id)?;
+            seller_stats.record_sale(listing.seller, listing.payment_mint, listing.price, now);
+            seller_stats.exit(ctx.program_id)?;
+            record_collection_sale(
+                collection_stats.as_mut(),
+                nft_metadata.as_ref(),
+                listing.payment_mint,
+                listing.price,
+                now,
+            )?;
+            if let Some(collection_stats) = &collection_stats {
+                collection_stats.exit(ctx.program_id)?;
+            }
+
+            total_price = new_total;
+            items_bought += 1;
+        }
 
-        listing.status = ListingStatus::Sold;
-        listing.buyer = Some(ctx.accounts.buyer.key());
-        listing.sold_at = Some(Clock::get()?.unix_timestamp);
+        require!(items_bought > 0, ErrorCode::NothingSwept);
+        ctx.accounts.marketplace.total_sales += items_bought as u64;
 
-        emit!(ListingSold {
-            listing: listing.key(),
-            seller: listing.seller,
-            buyer: ctx.accounts.buyer.key(),
-            nft_mint: listing.nft_mint,
-            price: listing.price,
-            platform_fee,
-            timestamp: Clock::get()?.unix_timestamp,
+        emit!(SweepExecuted {
+            buyer,
+            payment_mint,
+            items_bought,
+            items_requested: (remaining.len() / SWEEP_ACCOUNTS_PER_LISTING) as u8,
+            total_price,
+            timestamp: now,
         });
 
         Ok(())
     }
 
-    /// Place a bid on an auction
-    pub fn place_bid(ctx: Context<PlaceBid>, amount: u64) -> Result<()> {
-        let listing = &ctx.accounts.listing;
-        let auction = &mut ctx.accounts.auction;
+    /// Complete a purchase relayed from another chain by the ZetaChain gateway
+    ///
+    /// The gateway withdraws the buyer's ZRC-20 payment as its SPL counterpart
+    /// into the cross-chain vault (ATA of the `cross_chain` PDA) and then calls
+    /// this instruction. The NFT goes to the Solana recipient named in the
+    /// message and any overpayment is returned to them. If the listing can no
+    /// longer be bought, the payment stays in the vault and the receipt is
+    /// marked `Failed` so `on_revert` can refund it.
+    pub fn on_call(
+        ctx: Context<OnCall>,
+        amount: u64,
+        sender: [u8; 20],
+        message: CrossChainPurchase,
+    ) -> Result<()> {
         let marketplace = &ctx.accounts.marketplace;
+        let listing = &ctx.accounts.listing;
+        let now = Clock::get()?.unix_timestamp;
 
         require!(!marketplace.paused, ErrorCode::MarketplacePaused);
-        require!(listing.status == ListingStatus::Active, ErrorCode::ListingNotActive);
-        require!(listing.listing_type == ListingType::Auction, ErrorCode::NotAuction);
         require!(
-            Clock::get()?.unix_timestamp < listing.expires_at,
-            ErrorCode::ListingExpired
+            ctx.accounts.payment_vault.amount >= amount,
+            ErrorCode::InsufficientCrossChainPayment
         );
-        require!(amount > auction.current_bid, ErrorCode::BidTooLow);
-
-        if auction.current_bid > 0 {
-            // Must be at least 5% higher
-            require!(
-                amount >= auction.current_bid * 105 / 100,
-                ErrorCode::InsufficientBidIncrease
-            );
 
-            // Refund previous bidder
-            let refund_accounts = TokenTransfer {
-                from: ctx.accounts.escrow_payment_account.to_account_info(),
-                to: ctx.accounts.previous_bidder_account.to_account_info(),
-                authority: ctx.accounts.auction.to_account_info(),
-            };
-            let seeds = &[
-                b"auction",
-                listing.key().as_ref(),
-                &[ctx.bumps.auction],
-            ];
-            let signer = &[&seeds[..]];
-            let refund_ctx = CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                refund_accounts,
-                signer,
-            );
-            token::transfer(refund_ctx, auction.current_bid)?;
-        }
+        let available = listing.status == ListingStatus::Active
+            && listing.listing_type == ListingType::FixedPrice
+            && listing.usd_price.is_none()
+            && listing.buyer_allowed(&message.recipient, &[])
+            && listing.token_gate.is_none()
+            && now < listing.expires_at
+            && amount >= listing.price;
 
-        // Transfer new bid to escrow
-        let bid_accounts = TokenTransfer {
-            from: ctx.accounts.bidder_payment_account.to_account_info(),
-            to: ctx.accounts.escrow_payment_account.to_account_info(),
-            authority: ctx.accounts.bidder.to_account_info(),
-        };
-        let bid_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), bid_accounts);
-        token::transfer(bid_ctx, amount)?;
+        let seeds = &[b"cross_chain".as_ref(), &[ctx.bumps.cross_chain_config]];
+        let signer = &[&seeds[..]];
 
-        auction.current_bid = amount;
-        auction.current_bidder = ctx.accounts.bidder.key();
-        auction.bid_count += 1;
+        let mut platform_fee = 0;
+        if available {
+            platform_fee = FixedPriceSale {
+                marketplace,
+                marketplace_bump: ctx.bumps.marketplace,
+                listing,
+                listing_bump: ctx.bumps.listing,
+                fee_bps: marketplace.platform_fee,
+                payer: ctx.accounts.cross_chain_config.to_account_info(),
+                payer_signer: signer,
+                buyer: message.recipient,
+                payer_payment_account: &ctx.accounts.payment_vault,
+                buyer_rewards_account: &ctx.accounts.recipient_payment_account,
+                buyer_nft_account: &ctx.accounts.recipient_nft_account,
+                seller_payment_account: &ctx.accounts.seller_payment_account,
+                fee_collector_account: &ctx.accounts.fee_collector_account,
+                escrow_nft_account: &ctx.accounts.escrow_nft_account,
+                token_program: &ctx.accounts.token_program,
+                rewards_vault: ctx.accounts.rewards_vault.as_ref(),
+                referral: None,
+            }
+            .execute()?;
+
+            // Return overpayment to the recipient
+            let excess = amount - listing.price;
+            if excess > 0 {
+                let cpi_accounts = TokenTransfer {
+                    from: ctx.accounts.payment_vault.to_account_info(),
+                    to: ctx.accounts.recipient_payment_account.to_account_info(),
+                    authority: ctx.accounts.cross_chain_config.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    signer,
+                );
+                token::transfer(cpi_ctx, excess)?;
+            }
+        }
 
-        if amount >= listing.price {
-            auction.reserve_met = true;
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.route_id = message.route_id;
+        receipt.source_chain = message.source_chain;
+        receipt.sender = sender;
+        receipt.listing = ctx.accounts.listing.key();
+        receipt.recipient = message.recipient;
+        receipt.payment_mint = ctx.accounts.listing.payment_mint;
+        receipt.amount = amount;
+        receipt.created_at = now;
+
+        if !available {
+            receipt.status = CrossChainStatus::Failed;
+            emit!(CrossChainPurchaseFailed {
+                route_id: message.route_id,
+                source_chain: message.source_chain,
+                listing: receipt.listing,
+                recipient: message.recipient,
+                amount,
+                timestamp: now,
+            });
+            return Ok(());
         }
 
-        emit!(BidPlaced {
-            listing: listing.key(),
-            bidder: ctx.accounts.bidder.key(),
+        receipt.status = CrossChainStatus::Completed;
+        ctx.accounts.marketplace.total_sales += 1;
+        let listing = &ctx.accounts.listing;
+        ctx.accounts
+            .seller_stats
+            .record_sale(listing.seller, listing.payment_mint, listing.price, now);
+        emit!(CrossChainPurchaseCompleted {
+            route_id: message.route_id,
+            source_chain: message.source_chain,
+            listing: receipt.listing,
+            recipient: message.recipient,
             amount,
-            bid_count: auction.bid_count,
+            timestamp: now,
+        });
+
+        mark_sold(&mut ctx.accounts.listing, message.recipient, platform_fee)
+    }
+
+    /// Refund a cross-chain purchase that could not be delivered
+    ///
+    /// Called by the gateway for receipts left `Failed` by `on_call`; the held
+    /// payment is returned to the message's Solana recipient.
+    pub fn on_revert(ctx: Context<OnRevert>, route_id: [u8; 32]) -> Result<()> {
+        let receipt = &ctx.accounts.receipt;
+        require!(receipt.status == CrossChainStatus::Failed, ErrorCode::NotRefundable);
+
+        let seeds = &[b"cross_chain".as_ref(), &[ctx.bumps.cross_chain_config]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.payment_vault.to_account_info(),
+            to: ctx.accounts.recipient_payment_account.to_account_info(),
+            authority: ctx.accounts.cross_chain_config.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, receipt.amount)?;
+
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.status = CrossChainStatus::Refunded;
+
+        emit!(CrossChainPurchaseRefunded {
+            route_id,
+            source_chain: receipt.source_chain,
+            listing: receipt.listing,
+            recipient: receipt.recipient,
+            amount: receipt.amount,
             timestamp: Clock::get()?.unix_timestamp,
         });
 
         Ok(())
     }
 
+    /// Place a bid on an auction
+    pub fn place_bid(ctx: Context<PlaceBid>, amount: u64) -> Result<()> {
+        execute_bid(ctx, amount, &[])
+    }
+
+    /// `place_bid` for auctions restricted to an allowlist, with the bidder's Merkle proof
+    pub fn place_bid_with_proof(ctx: Context<PlaceBid>, amount: u64, proof: Vec<[u8; 32]>) -> Result<()> {
+        execute_bid(ctx, amount, &proof)
+    }
+
     /// Settle an ended auction
     pub fn settle_auction(ctx: Context<SettleAuction>) -> Result<()> {
-        let listing = &mut ctx.accounts.listing;
+        let listing = &ctx.accounts.listing;
+        let listing_key = listing.key();
         let auction = &ctx.accounts.auction;
         let marketplace = &ctx.accounts.marketplace;
 
@@ -287,7 +658,7 @@ pub mod soundchain_marketplace {
             // Transfer payment to seller (from escrow)
             let seeds = &[
                 b"auction",
-                listing.key().as_ref(),
+                listing_key.as_ref(),
                 &[ctx.bumps.auction],
             ];
             let signer = &[&seeds[..]];
@@ -304,7 +675,25 @@ pub mod soundchain_marketplace {
             );
             token::transfer(seller_ctx, seller_amount)?;
 
-            // Transfer fee
+            // Transfer fee, less the referrer's share
+            let referral_fee = match auction.referrer {
+                Some(wallet) => Referral::for_wallet(
+                    wallet,
+                    ctx.accounts.referrer_payment_account.as_ref(),
+                    &listing.payment_mint,
+                )?
+                .pay(
+                    marketplace,
+                    listing_key,
+                    platform_fee,
+                    &ctx.accounts.escrow_payment_account,
+                    ctx.accounts.auction.to_account_info(),
+                    signer,
+                    &ctx.accounts.token_program,
+                )?,
+                None => 0,
+            };
+
             let fee_accounts = TokenTransfer {
                 from: ctx.accounts.escrow_payment_account.to_account_info(),
                 to: ctx.accounts.fee_collector_account.to_account_info(),
@@ -315,7 +704,7 @@ pub mod soundchain_marketplace {
                 fee_accounts,
                 signer,
             );
-            token::transfer(fee_ctx, platform_fee)?;
+            token::transfer(fee_ctx, platform_fee - referral_fee)?;
 
             // Transfer NFT to winner
             let listing_seeds = &[
@@ -337,12 +726,49 @@ pub mod soundchain_marketplace {
             );
             token::transfer(nft_ctx, 1)?;
 
+            let reward = marketplace.trading_reward(listing, auction.current_bid);
+            if reward > 0 {
+                let rewards_vault = ctx
+                    .accounts
+                    .rewards_vault
+                    .as_ref()
+                    .ok_or(ErrorCode::RewardsVaultRequired)?;
+                let winner_rewards_account = ctx
+                    .accounts
+                    .winner_rewards_account
+                    .as_ref()
+                    .ok_or(ErrorCode::RewardsAccountRequired)?;
+                pay_trading_rewards(
+                    marketplace,
+                    ctx.bumps.marketplace,
+                    rewards_vault,
+                    &ctx.accounts.seller_payment_account,
+                    winner_rewards_account,
+                    &ctx.accounts.token_program,
+                    reward,
+                )?;
+            }
+
+            let now = Clock::get()?.unix_timestamp;
+            ctx.accounts.marketplace.total_sales += 1;
+            ctx.accounts
+                .seller_stats
+                .record_sale(listing.seller, listing.payment_mint, auction.current_bid, now);
+            record_collection_sale(
+                ctx.accounts.collection_stats.as_mut(),
+                ctx.accounts.nft_metadata.as_ref(),
+                listing.payment_mint,
+                auction.current_bid,
+                now,
+            )?;
+
+            let listing = &mut ctx.accounts.listing;
             listing.status = ListingStatus::Sold;
             listing.buyer = Some(auction.current_bidder);
-            listing.sold_at = Some(Clock::get()?.unix_timestamp);
+            listing.sold_at = Some(now);
 
             emit!(ListingSold {
-                listing: listing.key(),
+                listing: listing_key,
                 seller: listing.seller,
                 buyer: auction.current_bidder,
                 nft_mint: listing.nft_mint,
@@ -371,7 +797,15 @@ pub mod soundchain_marketplace {
             );
             token::transfer(nft_ctx, 1)?;
 
+            let listing = &mut ctx.accounts.listing;
             listing.status = ListingStatus::Expired;
+
+            emit!(ListingExpired {
+                listing: listing_key,
+                seller: listing.seller,
+                nft_mint: listing.nft_mint,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
         }
 
         Ok(())
@@ -379,7 +813,7 @@ pub mod soundchain_marketplace {
 
     /// Cancel a listing
     pub fn cancel_listing(ctx: Context<CancelListing>) -> Result<()> {
-        let listing = &mut ctx.accounts.listing;
+        let listing = &ctx.accounts.listing;
 
         require!(listing.status == ListingStatus::Active, ErrorCode::ListingNotActive);
         require!(
@@ -389,7 +823,7 @@ pub mod soundchain_marketplace {
 
         // For auctions, ensure no active bids
         if listing.listing_type == ListingType::Auction {
-            let auction = &ctx.accounts.auction;
+            let auction = ctx.accounts.auction.as_ref().ok_or(ErrorCode::NotAuction)?;
             require!(auction.current_bid == 0, ErrorCode::HasActiveBid);
         }
 
@@ -413,6 +847,7 @@ pub mod soundchain_marketplace {
         );
         token::transfer(nft_ctx, 1)?;
 
+        let listing = &mut ctx.accounts.listing;
         listing.status = ListingStatus::Cancelled;
 
         emit!(ListingCancelled {
@@ -425,227 +860,2675 @@ pub mod soundchain_marketplace {
         Ok(())
     }
 
-    /// Pause/unpause marketplace (admin only)
-    pub fn set_paused(ctx: Context<AdminAction>, paused: bool) -> Result<()> {
-        let marketplace = &mut ctx.accounts.marketplace;
-        marketplace.paused = paused;
+    /// Return an expired fixed-price listing's NFT to the seller (anyone can crank)
+    pub fn expire_listing(ctx: Context<ExpireListing>) -> Result<()> {
+        let listing = &ctx.accounts.listing;
+
+        require!(listing.status == ListingStatus::Active, ErrorCode::ListingNotActive);
+        require!(listing.listing_type == ListingType::FixedPrice, ErrorCode::NotFixedPrice);
+        require!(
+            Clock::get()?.unix_timestamp >= listing.expires_at,
+            ErrorCode::ListingNotExpired
+        );
+
+        let seeds = &[
+            b"listing",
+            listing.nft_mint.as_ref(),
+            &[ctx.bumps.listing],
+        ];
+        let signer = &[&seeds[..]];
+
+        let nft_accounts = TokenTransfer {
+            from: ctx.accounts.escrow_nft_account.to_account_info(),
+            to: ctx.accounts.seller_nft_account.to_account_info(),
+            authority: ctx.accounts.listing.to_account_info(),
+        };
+        let nft_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            nft_accounts,
+            signer,
+        );
+        token::transfer(nft_ctx, 1)?;
+
+        let listing = &mut ctx.accounts.listing;
+        listing.status = ListingStatus::Expired;
+
+        emit!(ListingExpired {
+            listing: listing.key(),
+            seller: listing.seller,
+            nft_mint: listing.nft_mint,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
         Ok(())
     }
 
-    /// Update platform fee (admin only)
-    pub fn set_fee(ctx: Context<AdminAction>, new_fee: u16) -> Result<()> {
-        require!(new_fee <= 1000, ErrorCode::FeeTooHigh); // Max 10%
-        let marketplace = &mut ctx.accounts.marketplace;
-        marketplace.platform_fee = new_fee;
+    /// Accept other payment mints for a fixed price listing, each at its own
+    /// price, replacing any set before (seller only)
+    pub fn set_listing_prices(ctx: Context<SetListingPrices>, prices: Vec<MintPrice>) -> Result<()> {
+        let listing = &ctx.accounts.listing;
+        require!(listing.status == ListingStatus::Active, ErrorCode::ListingNotActive);
+        require!(listing.listing_type == ListingType::FixedPrice, ErrorCode::NotFixedPrice);
+        require!(listing.usd_price.is_none(), ErrorCode::UsdPriced);
+        require!(prices.len() <= MAX_EXTRA_PAYMENT_MINTS, ErrorCode::TooManyPaymentMints);
+        for (i, price) in prices.iter().enumerate() {
+            require!(
+                price.mint != listing.payment_mint && !prices[..i].iter().any(|other| other.mint == price.mint),
+                ErrorCode::DuplicatePaymentMint
+            );
+        }
+
+        let listing_prices = &mut ctx.accounts.listing_prices;
+        listing_prices.listing = listing.key();
+        listing_prices.prices = prices.clone();
+
+        emit!(ListingPricesSet {
+            listing: listing.key(),
+            prices,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
         Ok(())
     }
-}
 
-// ============ Enums ============
+    /// Close a listing's extra payment mints, refunding the rent (seller only)
+    pub fn close_listing_prices(_ctx: Context<CloseListingPrices>) -> Result<()> {
+        Ok(())
+    }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
-pub enum ListingType {
-    FixedPrice,
-    Auction,
-    MakeOffer,
-}
+    /// Restrict who can buy or bid on a listing, or open it to anyone with
+    /// `None` (seller only). Auctions can only be restricted before the first bid.
+    ///
+    /// Sweeps skip restricted listings, and cross-chain purchases only go
+    /// through for a single allowed buyer who is the recipient.
+    pub fn set_buyer_restriction(
+        ctx: Context<SetListingAccess>,
+        restriction: Option<BuyerRestriction>,
+    ) -> Result<()> {
+        let listing = &mut ctx.accounts.listing;
+        require!(listing.status == ListingStatus::Active, ErrorCode::ListingNotActive);
+        if restriction.is_some() {
+            require_no_bids(listing, ctx.accounts.auction.as_ref())?;
+        }
+        // buy_usd has no proof argument
+        require!(
+            !(listing.usd_price.is_some() && matches!(restriction, Some(BuyerRestriction::Allowlist { .. }))),
+            ErrorCode::AllowlistNotSupported
+        );
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
-pub enum ListingStatus {
-    Active,
-    Sold,
-    Cancelled,
-    Expired,
-}
+        listing.restriction = restriction;
 
-// ============ Accounts ============
+        emit!(BuyerRestrictionSet {
+            listing: listing.key(),
+            restriction,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Require buyers and bidders to hold a token, or remove the requirement
+    /// with `None` (seller only). Auctions can only be gated before the first bid.
+    ///
+    /// Sweeps and cross-chain purchases skip gated listings.
+    pub fn set_token_gate(ctx: Context<SetListingAccess>, token_gate: Option<TokenGate>) -> Result<()> {
+        let listing = &mut ctx.accounts.listing;
+        require!(listing.status == ListingStatus::Active, ErrorCode::ListingNotActive);
+        if token_gate.is_some() {
+            require_no_bids(listing, ctx.accounts.auction.as_ref())?;
+        }
+        if let Some(TokenGate::MinBalance { amount, .. }) = token_gate {
+            require!(amount > 0, ErrorCode::InvalidTokenGate);
+        }
+
+        listing.token_gate = token_gate;
+
+        emit!(TokenGateSet {
+            listing: listing.key(),
+            token_gate,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Pause/unpause marketplace (pauser only)
+    pub fn set_paused(ctx: Context<RoleAction>, paused: bool) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::Pauser)?;
+        let marketplace = &mut ctx.accounts.marketplace;
+        marketplace.paused = paused;
+        Ok(())
+    }
+
+    /// Propose a new platform fee (fee manager only)
+    ///
+    /// Takes effect through `execute_fee` once `fee_delay` has passed, so
+    /// sellers get notice before their economics change.
+    pub fn propose_fee(ctx: Context<RoleAction>, new_fee: u16) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::FeeManager)?;
+        require!(new_fee <= MAX_PLATFORM_FEE, ErrorCode::FeeTooHigh);
+
+        let now = Clock::get()?.unix_timestamp;
+        let marketplace = &mut ctx.accounts.marketplace;
+        marketplace.propose_fee(new_fee, now);
+
+        emit!(FeeChangeProposed {
+            current_fee: marketplace.platform_fee,
+            new_fee,
+            execute_after: marketplace.fee_change_eta,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Apply a proposed platform fee after the timelock (fee manager only)
+    pub fn execute_fee(ctx: Context<RoleAction>) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::FeeManager)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let marketplace = &mut ctx.accounts.marketplace;
+        let old_fee = marketplace.platform_fee;
+        let new_fee = marketplace.execute_fee(now)?;
+
+        emit!(FeeChangeExecuted {
+            old_fee,
+            new_fee,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel a proposed platform fee (fee manager only)
+    pub fn cancel_fee(ctx: Context<RoleAction>) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::FeeManager)?;
+
+        let marketplace = &mut ctx.accounts.marketplace;
+        let new_fee = marketplace.pending_fee.take().ok_or(ErrorCode::NoPendingFee)?;
+        marketplace.fee_change_eta = 0;
+
+        emit!(FeeChangeCancelled {
+            new_fee,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Update the fee change timelock (admin only)
+    pub fn set_fee_delay(ctx: Context<AdminAction>, fee_delay: i64) -> Result<()> {
+        require!(
+            (MIN_FEE_DELAY..=MAX_FEE_DELAY).contains(&fee_delay),
+            ErrorCode::InvalidFeeDelay
+        );
+        let marketplace = &mut ctx.accounts.marketplace;
+        marketplace.fee_delay = fee_delay;
+        Ok(())
+    }
+
+    /// Propose a new marketplace authority (admin only)
+    ///
+    /// The handover completes when the proposed key calls `accept_authority`.
+    pub fn propose_authority(ctx: Context<AdminAction>, new_authority: Pubkey) -> Result<()> {
+        require!(new_authority != Pubkey::default(), ErrorCode::InvalidAuthority);
+        let marketplace = &mut ctx.accounts.marketplace;
+        marketplace.pending_authority = Some(new_authority);
+
+        emit!(AuthorityProposed {
+            authority: marketplace.authority,
+            pending_authority: new_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Accept a proposed authority handover (pending authority only)
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let marketplace = &mut ctx.accounts.marketplace;
+        let previous_authority = marketplace.authority;
+        marketplace.authority = ctx.accounts.pending_authority.key();
+        marketplace.pending_authority = None;
+
+        emit!(AuthorityTransferred {
+            previous_authority,
+            new_authority: marketplace.authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel a pending authority handover (admin only)
+    pub fn cancel_authority_transfer(ctx: Context<AdminAction>) -> Result<()> {
+        let marketplace = &mut ctx.accounts.marketplace;
+        let pending_authority = marketplace
+            .pending_authority
+            .take()
+            .ok_or(ErrorCode::NoPendingAuthority)?;
+
+        emit!(AuthorityTransferCancelled {
+            authority: marketplace.authority,
+            pending_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Configure OGUN trading rewards and create the rewards vault (admin only)
+    pub fn initialize_rewards(
+        ctx: Context<InitializeRewards>,
+        rewards_rate: u16,
+        rewards_limit: u64,
+    ) -> Result<()> {
+        require!(rewards_rate <= 10000, ErrorCode::RewardsRateTooHigh);
+        let marketplace = &mut ctx.accounts.marketplace;
+        marketplace.rewards_mint = ctx.accounts.rewards_mint.key();
+        marketplace.rewards_vault = ctx.accounts.rewards_vault.key();
+        marketplace.rewards_rate = rewards_rate; // Basis points of sale price
+        marketplace.rewards_limit = rewards_limit;
+        Ok(())
+    }
+
+    /// Fund the trading rewards vault (treasury manager only)
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::TreasuryManager)?;
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.holder_token_account.to_account_info(),
+            to: ctx.accounts.rewards_vault.to_account_info(),
+            authority: ctx.accounts.holder.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+
+    /// Update trading rewards rate (fee manager only)
+    pub fn set_rewards_rate(ctx: Context<RoleAction>, rewards_rate: u16) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::FeeManager)?;
+        require!(rewards_rate <= 10000, ErrorCode::RewardsRateTooHigh);
+        let marketplace = &mut ctx.accounts.marketplace;
+        marketplace.rewards_rate = rewards_rate;
+        Ok(())
+    }
+
+    /// Update trading rewards cap per party per sale (fee manager only)
+    pub fn set_rewards_limit(ctx: Context<RoleAction>, rewards_limit: u64) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::FeeManager)?;
+        let marketplace = &mut ctx.accounts.marketplace;
+        marketplace.rewards_limit = rewards_limit;
+        Ok(())
+    }
+
+    /// Reclaim tokens from the trading rewards vault (treasury manager only)
+    pub fn reclaim_rewards(ctx: Context<ReclaimRewards>, amount: u64) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::TreasuryManager)?;
+        let seeds = &[b"marketplace".as_ref(), &[ctx.bumps.marketplace]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.rewards_vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+
+    /// Set up cross-chain purchases with the ZetaChain gateway signer (admin only)
+    pub fn initialize_cross_chain(ctx: Context<InitializeCrossChain>, gateway: Pubkey) -> Result<()> {
+        let cross_chain_config = &mut ctx.accounts.cross_chain_config;
+        cross_chain_config.gateway = gateway;
+        Ok(())
+    }
+
+    /// Update the ZetaChain gateway signer (cross-chain verifier only)
+    pub fn set_gateway(ctx: Context<SetGateway>, gateway: Pubkey) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::CrossChainVerifier)?;
+        let cross_chain_config = &mut ctx.accounts.cross_chain_config;
+        cross_chain_config.gateway = gateway;
+        Ok(())
+    }
+
+    /// Update the platform fee collector (treasury manager only)
+    ///
+    /// Fee accounts must be `ATA(payment_mint, fee_collector)`.
+    pub fn update_fee_collector(ctx: Context<RoleAction>, new_fee_collector: Pubkey) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::TreasuryManager)?;
+        require!(new_fee_collector != Pubkey::default(), ErrorCode::InvalidFeeCollector);
+
+        let marketplace = &mut ctx.accounts.marketplace;
+        let old_fee_collector = marketplace.fee_collector;
+        marketplace.fee_collector = new_fee_collector;
+
+        emit!(FeeCollectorUpdated {
+            old_fee_collector,
+            new_fee_collector,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Configure OGUN holder and staker fee discounts (fee manager only)
+    ///
+    /// Discounts are in basis points of the platform fee; buyers get the
+    /// larger of the two they qualify for.
+    pub fn set_fee_schedule(
+        ctx: Context<SetFeeSchedule>,
+        ogun_mint: Pubkey,
+        staking_pool: Pubkey,
+        holder_threshold: u64,
+        holder_discount: u16,
+        staker_threshold: u64,
+        staker_discount: u16,
+    ) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::FeeManager)?;
+        require!(
+            holder_discount <= 10000 && staker_discount <= 10000,
+            ErrorCode::DiscountTooHigh
+        );
+
+        let fee_schedule = &mut ctx.accounts.fee_schedule;
+        fee_schedule.ogun_mint = ogun_mint;
+        fee_schedule.staking_pool = staking_pool;
+        fee_schedule.holder_threshold = holder_threshold;
+        fee_schedule.holder_discount = holder_discount;
+        fee_schedule.staker_threshold = staker_threshold;
+        fee_schedule.staker_discount = staker_discount;
+        Ok(())
+    }
+
+    /// Set a reduced platform fee for a verified collection (fee manager only)
+    pub fn set_collection_fee(ctx: Context<SetCollectionFee>, collection: Pubkey, fee: u16) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::FeeManager)?;
+        require!(fee <= MAX_PLATFORM_FEE, ErrorCode::FeeTooHigh);
+
+        let collection_fee = &mut ctx.accounts.collection_fee;
+        collection_fee.collection = collection;
+        collection_fee.fee = fee;
+        Ok(())
+    }
+
+    /// Remove a collection fee override (fee manager only)
+    pub fn remove_collection_fee(ctx: Context<RemoveCollectionFee>) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::FeeManager)
+    }
+
+    /// Accept `payment_mint` for USD listings, priced by a Pyth price update
+    /// account for `feed_id` quoting USD per token (treasury manager only)
+    pub fn set_price_feed(
+        ctx: Context<SetPriceFeed>,
+        oracle: Pubkey,
+        feed_id: [u8; 32],
+        max_staleness: i64,
+        max_confidence: u16,
+    ) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::TreasuryManager)?;
+        require!(
+            max_staleness > 0 && max_confidence <= 10000,
+            ErrorCode::InvalidPriceFeedConfig
+        );
+
+        let price_feed = &mut ctx.accounts.price_feed;
+        price_feed.payment_mint = ctx.accounts.payment_mint.key();
+        price_feed.oracle = oracle;
+        price_feed.decimals = ctx.accounts.payment_mint.decimals;
+        price_feed.max_staleness = max_staleness;
+        price_feed.max_confidence = max_confidence;
+        price_feed.feed_id = feed_id;
+        Ok(())
+    }
+
+    /// Stop accepting a payment mint for USD listings; its open USD listings
+    /// can't be bought until a feed is set again (treasury manager only)
+    pub fn remove_price_feed(ctx: Context<RemovePriceFeed>) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::TreasuryManager)
+    }
+
+    /// Set a partner platform fee for a seller (fee manager only)
+    pub fn set_partner_fee(ctx: Context<SetPartnerFee>, seller: Pubkey, fee: u16) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::FeeManager)?;
+        require!(fee <= MAX_PLATFORM_FEE, ErrorCode::FeeTooHigh);
+
+        let partner_fee = &mut ctx.accounts.partner_fee;
+        partner_fee.seller = seller;
+        partner_fee.fee = fee;
+        Ok(())
+    }
+
+    /// Remove a seller's partner fee (fee manager only)
+    pub fn remove_partner_fee(ctx: Context<RemovePartnerFee>) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::FeeManager)
+    }
+
+    /// Create stats for a seller and payment mint, e.g. for listings made before stats existed
+    pub fn init_seller_stats(ctx: Context<InitSellerStats>, seller: Pubkey, payment_mint: Pubkey) -> Result<()> {
+        let seller_stats = &mut ctx.accounts.seller_stats;
+        seller_stats.key = seller;
+        seller_stats.payment_mint = payment_mint;
+        Ok(())
+    }
+
+    /// Create stats for a verified collection and payment mint
+    pub fn init_collection_stats(
+        ctx: Context<InitCollectionStats>,
+        collection: Pubkey,
+        payment_mint: Pubkey,
+    ) -> Result<()> {
+        let collection_stats = &mut ctx.accounts.collection_stats;
+        collection_stats.key = collection;
+        collection_stats.payment_mint = payment_mint;
+        Ok(())
+    }
+
+    /// Register as a referrer; payouts start once the admin approves
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        let referrer = &mut ctx.accounts.referrer;
+        referrer.wallet = ctx.accounts.wallet.key();
+        referrer.approved = false;
+        referrer.registered_at = Clock::get()?.unix_timestamp;
+
+        emit!(ReferrerRegistered {
+            referrer: referrer.wallet,
+            timestamp: referrer.registered_at,
+        });
+
+        Ok(())
+    }
+
+    /// Approve or suspend a referrer (admin only)
+    pub fn set_referrer_approved(ctx: Context<SetReferrerApproved>, approved: bool) -> Result<()> {
+        let referrer = &mut ctx.accounts.referrer;
+        referrer.approved = approved;
+
+        emit!(ReferrerApprovalUpdated {
+            referrer: referrer.wallet,
+            approved,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Update the referrers' share of the platform fee (fee manager only)
+    pub fn set_referral_share(ctx: Context<RoleAction>, referral_share: u16) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::FeeManager)?;
+        require!(referral_share <= 10000, ErrorCode::ReferralShareTooHigh);
+        let marketplace = &mut ctx.accounts.marketplace;
+        marketplace.referral_share = referral_share;
+        Ok(())
+    }
+
+    /// Grant a role to a key (admin only)
+    pub fn grant_role(ctx: Context<GrantRole>, role: Role, holder: Pubkey) -> Result<()> {
+        let role_assignment = &mut ctx.accounts.role_assignment;
+        role_assignment.role = role;
+        role_assignment.holder = holder;
+
+        emit!(RoleGranted {
+            role,
+            holder,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Revoke a role (admin only)
+    pub fn revoke_role(ctx: Context<RevokeRole>) -> Result<()> {
+        emit!(RoleRevoked {
+            role: ctx.accounts.role_assignment.role,
+            holder: ctx.accounts.role_assignment.holder,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Delist a listing and return the NFT to its seller (moderator only)
+    pub fn moderate_listing(ctx: Context<ModerateListing>) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::Moderator)?;
+        let listing = &ctx.accounts.listing;
+        require!(listing.status == ListingStatus::Active, ErrorCode::ListingNotActive);
+
+        // For auctions, ensure no active bids
+        if listing.listing_type == ListingType::Auction {
+            let auction = ctx.accounts.auction.as_ref().ok_or(ErrorCode::NotAuction)?;
+            require!(auction.current_bid == 0, ErrorCode::HasActiveBid);
+        }
+
+        // Return NFT to seller
+        let seeds = &[
+            b"listing",
+            listing.nft_mint.as_ref(),
+            &[ctx.bumps.listing],
+        ];
+        let signer = &[&seeds[..]];
+
+        let nft_accounts = TokenTransfer {
+            from: ctx.accounts.escrow_nft_account.to_account_info(),
+            to: ctx.accounts.seller_nft_account.to_account_info(),
+            authority: ctx.accounts.listing.to_account_info(),
+        };
+        let nft_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            nft_accounts,
+            signer,
+        );
+        token::transfer(nft_ctx, 1)?;
+
+        let listing = &mut ctx.accounts.listing;
+        listing.status = ListingStatus::Cancelled;
+
+        emit!(ListingCancelled {
+            listing: listing.key(),
+            seller: listing.seller,
+            nft_mint: listing.nft_mint,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Upgrade the marketplace to the current layout, growing it if needed
+    pub fn migrate_marketplace(ctx: Context<MigrateMarketplace>) -> Result<()> {
+        migrate_account(
+            &ctx.accounts.marketplace,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            MARKETPLACE_SPACE,
+            Marketplace::upgrade,
+        )?;
+
+        emit!(AccountMigrated {
+            account: ctx.accounts.marketplace.key(),
+            version: MARKETPLACE_VERSION,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Upgrade a listing to the current layout, growing it if needed
+    pub fn migrate_listing(ctx: Context<MigrateAccount>) -> Result<()> {
+        migrate_account(
+            &ctx.accounts.account,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            LISTING_SPACE,
+            |listing: &mut Listing| listing.version = LISTING_VERSION,
+        )?;
+
+        emit!(AccountMigrated {
+            account: ctx.accounts.account.key(),
+            version: LISTING_VERSION,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Upgrade an auction to the current layout, growing it if needed
+    pub fn migrate_auction(ctx: Context<MigrateAccount>) -> Result<()> {
+        migrate_account(
+            &ctx.accounts.account,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            AUCTION_SPACE,
+            |auction: &mut Auction| auction.version = AUCTION_VERSION,
+        )?;
+
+        emit!(AccountMigrated {
+            account: ctx.accounts.account.key(),
+            version: AUCTION_VERSION,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+// ============ Helpers ============
+
+/// Accounts needed to pay out a fixed price sale
+struct FixedPriceSale<'a, 'info> {
+    marketplace: &'a Account<'info, Marketplace>,
+    marketplace_bump: u8,
+    listing: &'a Account<'info, Listing>,
+    listing_bump: u8,
+    fee_bps: u16,
+    payer: AccountInfo<'info>,
+    payer_signer: &'a [&'a [&'a [u8]]],
+    buyer: Pubkey,
+    payer_payment_account: &'a Account<'info, TokenAccount>,
+    buyer_rewards_account: &'a Account<'info, TokenAccount>,
+    buyer_nft_account: &'a Account<'info, TokenAccount>,
+    seller_payment_account: &'a Account<'info, TokenAccount>,
+    fee_collector_account: &'a Account<'info, TokenAccount>,
+    escrow_nft_account: &'a Account<'info, TokenAccount>,
+    token_program: &'a Program<'info, Token>,
+    rewards_vault: Option<&'a Account<'info, TokenAccount>>,
+    referral: Option<Referral<'a, 'info>>,
+}
+
+impl<'a, 'info> FixedPriceSale<'a, 'info> {
+    /// Pay seller and platform fee, release the NFT and pay trading rewards;
+    /// returns the platform fee
+    fn execute(&self) -> Result<u64> {
+        let listing = self.listing;
+        let marketplace = self.marketplace;
+
+        // Calculate fees
+        let platform_fee = (listing.price as u128 * self.fee_bps as u128 / 10000) as u64;
+        let seller_amount = listing.price - platform_fee;
+
+        // Transfer payment from buyer
+        let cpi_accounts = TokenTransfer {
+            from: self.payer_payment_account.to_account_info(),
+            to: self.seller_payment_account.to_account_info(),
+            authority: self.payer.clone(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+            .with_signer(self.payer_signer);
+        token::transfer(cpi_ctx, seller_amount)?;
+
+        // Transfer platform fee, less the referrer's share
+        let referral_fee = match &self.referral {
+            Some(referral) => referral.pay(
+                marketplace,
+                listing.key(),
+                platform_fee,
+                self.payer_payment_account,
+                self.payer.clone(),
+                self.payer_signer,
+                self.token_program,
+            )?,
+            None => 0,
+        };
+
+        let fee_accounts = TokenTransfer {
+            from: self.payer_payment_account.to_account_info(),
+            to: self.fee_collector_account.to_account_info(),
+            authority: self.payer.clone(),
+        };
+        let fee_ctx = CpiContext::new(self.token_program.to_account_info(), fee_accounts)
+            .with_signer(self.payer_signer);
+        token::transfer(fee_ctx, platform_fee - referral_fee)?;
+
+        // Transfer NFT to buyer (from escrow)
+        let seeds = &[
+            b"listing",
+            listing.nft_mint.as_ref(),
+            &[self.listing_bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let nft_accounts = TokenTransfer {
+            from: self.escrow_nft_account.to_account_info(),
+            to: self.buyer_nft_account.to_account_info(),
+            authority: listing.to_account_info(),
+        };
+        let nft_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            nft_accounts,
+            signer,
+        );
+        token::transfer(nft_ctx, 1)?;
+
+        let reward = marketplace.trading_reward(listing, listing.price);
+        if reward > 0 {
+            let rewards_vault = self.rewards_vault.ok_or(ErrorCode::RewardsVaultRequired)?;
+            require_keys_eq!(
+                self.seller_payment_account.owner,
+                listing.seller,
+                ErrorCode::RewardsAccountMismatch
+            );
+            require_keys_eq!(
+                self.buyer_rewards_account.owner,
+                self.buyer,
+                ErrorCode::RewardsAccountMismatch
+            );
+            pay_trading_rewards(
+                marketplace,
+                self.marketplace_bump,
+                rewards_vault,
+                self.seller_payment_account,
+                self.buyer_rewards_account,
+                self.token_program,
+                reward,
+            )?;
+        }
+
+        Ok(platform_fee)
+    }
+}
+
+/// Place a bid, refunding the previous bidder
+fn execute_bid(ctx: Context<PlaceBid>, amount: u64, proof: &[[u8; 32]]) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+    let listing_key = listing.key();
+    let auction_info = ctx.accounts.auction.to_account_info();
+    let auction = &mut ctx.accounts.auction;
+    let marketplace = &ctx.accounts.marketplace;
+
+    require!(!marketplace.paused, ErrorCode::MarketplacePaused);
+    require!(listing.status == ListingStatus::Active, ErrorCode::ListingNotActive);
+    require!(listing.listing_type == ListingType::Auction, ErrorCode::NotAuction);
+    require!(
+        Clock::get()?.unix_timestamp < listing.expires_at,
+        ErrorCode::ListingExpired
+    );
+    require!(listing.buyer_allowed(&ctx.accounts.bidder.key(), proof), ErrorCode::BuyerNotAllowed);
+    check_token_gate(
+        listing,
+        &ctx.accounts.bidder.key(),
+        ctx.accounts.gate_token_account.as_ref(),
+        ctx.accounts.gate_nft_metadata.as_ref(),
+    )?;
+    require!(amount > auction.current_bid, ErrorCode::BidTooLow);
+
+    if auction.current_bid > 0 {
+        // Must be at least 5% higher
+        require!(
+            amount >= auction.current_bid * 105 / 100,
+            ErrorCode::InsufficientBidIncrease
+        );
+
+        // Refund previous bidder
+        let refund_accounts = TokenTransfer {
+            from: ctx.accounts.escrow_payment_account.to_account_info(),
+            to: ctx.accounts.previous_bidder_account.to_account_info(),
+            authority: auction_info,
+        };
+        let seeds = &[
+            b"auction",
+            listing_key.as_ref(),
+            &[ctx.bumps.auction],
+        ];
+        let signer = &[&seeds[..]];
+        let refund_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            refund_accounts,
+            signer,
+        );
+        token::transfer(refund_ctx, auction.current_bid)?;
+    }
+
+    // Transfer new bid to escrow
+    let bid_accounts = TokenTransfer {
+        from: ctx.accounts.bidder_payment_account.to_account_info(),
+        to: ctx.accounts.escrow_payment_account.to_account_info(),
+        authority: ctx.accounts.bidder.to_account_info(),
+    };
+    let bid_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), bid_accounts);
+    token::transfer(bid_ctx, amount)?;
+
+    auction.current_bid = amount;
+    auction.current_bidder = ctx.accounts.bidder.key();
+    auction.bid_count += 1;
+
+    // The winning bid's referrer is paid at settlement
+    auction.referrer = match &ctx.accounts.referrer {
+        Some(referrer) => {
+            require!(referrer.approved, ErrorCode::ReferrerNotApproved);
+            Some(referrer.wallet)
+        }
+        None => None,
+    };
+
+    if amount >= listing.price {
+        auction.reserve_met = true;
+    }
+
+    emit!(BidPlaced {
+        listing: listing_key,
+        bidder: ctx.accounts.bidder.key(),
+        amount,
+        bid_count: auction.bid_count,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Resolve the payment mint the buyer chose, then sell
+fn buy_listing(ctx: Context<Buy>, proof: &[[u8; 32]]) -> Result<()> {
+    require!(ctx.accounts.listing.usd_price.is_none(), ErrorCode::UsdPriced);
+
+    let payment_mint = ctx.accounts.buyer_payment_account.mint;
+    if payment_mint != ctx.accounts.listing.payment_mint {
+        let price = ctx
+            .accounts
+            .listing_prices
+            .as_ref()
+            .and_then(|listing_prices| listing_prices.price_for(&payment_mint))
+            .ok_or(ErrorCode::PaymentMintNotAccepted)?;
+
+        // Sold like a listing in the chosen mint, which stays on the listing
+        let listing = &mut ctx.accounts.listing;
+        listing.payment_mint = payment_mint;
+        listing.price = price;
+    }
+
+    execute_buy(ctx, proof)
+}
+
+/// Sell a fixed price listing at `listing.price` to a buyer the listing allows
+fn execute_buy(ctx: Context<Buy>, proof: &[[u8; 32]]) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+    let marketplace = &ctx.accounts.marketplace;
+
+    require!(!marketplace.paused, ErrorCode::MarketplacePaused);
+    require!(listing.status == ListingStatus::Active, ErrorCode::ListingNotActive);
+    require!(listing.listing_type == ListingType::FixedPrice, ErrorCode::NotFixedPrice);
+    require!(
+        Clock::get()?.unix_timestamp < listing.expires_at,
+        ErrorCode::ListingExpired
+    );
+    require!(listing.buyer_allowed(&ctx.accounts.buyer.key(), proof), ErrorCode::BuyerNotAllowed);
+    check_token_gate(
+        listing,
+        &ctx.accounts.buyer.key(),
+        ctx.accounts.gate_token_account.as_ref(),
+        ctx.accounts.gate_nft_metadata.as_ref(),
+    )?;
+
+    let (fee_bps, discount) = buy_fee_bps(ctx.accounts)?;
+
+    let platform_fee = FixedPriceSale {
+        marketplace,
+        marketplace_bump: ctx.bumps.marketplace,
+        listing,
+        listing_bump: ctx.bumps.listing,
+        fee_bps,
+        payer: ctx.accounts.buyer.to_account_info(),
+        payer_signer: &[],
+        buyer: ctx.accounts.buyer.key(),
+        payer_payment_account: &ctx.accounts.buyer_payment_account,
+        buyer_rewards_account: &ctx.accounts.buyer_payment_account,
+        buyer_nft_account: &ctx.accounts.buyer_nft_account,
+        seller_payment_account: &ctx.accounts.seller_payment_account,
+        fee_collector_account: &ctx.accounts.fee_collector_account,
+        escrow_nft_account: &ctx.accounts.escrow_nft_account,
+        token_program: &ctx.accounts.token_program,
+        rewards_vault: ctx.accounts.rewards_vault.as_ref(),
+        referral: Referral::resolve(
+            ctx.accounts.referrer.as_ref(),
+            ctx.accounts.referrer_payment_account.as_ref(),
+            &listing.payment_mint,
+        )?,
+    }
+    .execute()?;
+
+    emit!(FeeApplied {
+        listing: listing.key(),
+        buyer: ctx.accounts.buyer.key(),
+        base_fee_bps: marketplace.platform_fee,
+        applied_fee_bps: fee_bps,
+        discount,
+        platform_fee,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.marketplace.total_sales += 1;
+    ctx.accounts
+        .seller_stats
+        .record_sale(listing.seller, listing.payment_mint, listing.price, now);
+    record_collection_sale(
+        ctx.accounts.collection_stats.as_mut(),
+        ctx.accounts.nft_metadata.as_ref(),
+        listing.payment_mint,
+        listing.price,
+        now,
+    )?;
+
+    mark_sold(&mut ctx.accounts.listing, ctx.accounts.buyer.key(), platform_fee)
+}
+
+/// Open a fixed price listing, priced in the payment mint or, with `usd_price`, in USD
+fn list_fixed_price(
+    ctx: Context<CreateListing>,
+    price: u64,
+    usd_price: Option<u64>,
+    duration: i64,
+    scid: Option<String>,
+) -> Result<()> {
+    let marketplace = &ctx.accounts.marketplace;
+    require!(!marketplace.paused, ErrorCode::MarketplacePaused);
+
+    record_listing(ctx.accounts)?;
+
+    let listing = &mut ctx.accounts.listing;
+    listing.seller = ctx.accounts.seller.key();
+    listing.nft_mint = ctx.accounts.nft_mint.key();
+    listing.payment_mint = ctx.accounts.payment_mint.key();
+    listing.price = price;
+    listing.listing_type = ListingType::FixedPrice;
+    listing.status = ListingStatus::Active;
+    listing.created_at = Clock::get()?.unix_timestamp;
+    listing.expires_at = Clock::get()?.unix_timestamp + duration;
+    listing.scid = scid;
+    listing.buyer = None;
+    listing.sold_at = None;
+    listing.version = LISTING_VERSION;
+    listing.usd_price = usd_price;
+    listing.restriction = None;
+    listing.token_gate = None;
+
+    // Transfer NFT to escrow
+    let cpi_accounts = TokenTransfer {
+        from: ctx.accounts.seller_nft_account.to_account_info(),
+        to: ctx.accounts.escrow_nft_account.to_account_info(),
+        authority: ctx.accounts.seller.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, 1)?;
+
+    emit!(ListingCreated {
+        listing: listing.key(),
+        seller: listing.seller,
+        nft_mint: listing.nft_mint,
+        price,
+        listing_type: ListingType::FixedPrice,
+        timestamp: listing.created_at,
+    });
+
+    if let Some(usd_price) = usd_price {
+        emit!(UsdListingCreated {
+            listing: listing.key(),
+            payment_mint: listing.payment_mint,
+            usd_price,
+            timestamp: listing.created_at,
+        });
+    }
+
+    Ok(())
+}
+
+/// Platform fee in basis points for a `buy`, with the OGUN discount applied
+fn buy_fee_bps(accounts: &Buy) -> Result<(u16, FeeDiscount)> {
+    let fee_bps = tiered_fee_bps(
+        accounts.marketplace.platform_fee,
+        accounts.collection_fee.as_deref(),
+        accounts.nft_metadata.as_deref(),
+        accounts.partner_fee.as_deref(),
+    )?;
+    discounted_fee_bps(
+        fee_bps,
+        accounts.fee_schedule.as_deref(),
+        accounts.buyer_ogun_account.as_deref(),
+        accounts.buyer_stake_account.as_deref(),
+        accounts.buyer.key(),
+    )
+}
+
+/// Platform fee in basis points for a listing, with its collection and
+/// seller's partner rates applied
+///
+/// Collection and partner rates only ever lower the fee below
+/// `platform_fee`, so a later fee cut still reaches every seller.
+fn tiered_fee_bps(
+    platform_fee: u16,
+    collection_fee: Option<&CollectionFee>,
+    nft_metadata: Option<&AccountInfo>,
+    partner_fee: Option<&PartnerFee>,
+) -> Result<u16> {
+    let mut fee_bps = platform_fee;
+
+    if let Some(collection_fee) = collection_fee {
+        let metadata = nft_metadata.ok_or(ErrorCode::MetadataRequired)?;
+        let collection = verified_collection(metadata)?;
+        require_keys_eq!(collection, collection_fee.collection, ErrorCode::CollectionMismatch);
+        fee_bps = fee_bps.min(collection_fee.fee);
+    }
+
+    if let Some(partner_fee) = partner_fee {
+        fee_bps = fee_bps.min(partner_fee.fee);
+    }
+
+    Ok(fee_bps)
+}
+
+/// Apply the buyer's OGUN holder or staker discount to `fee_bps`
+fn discounted_fee_bps(
+    fee_bps: u16,
+    fee_schedule: Option<&FeeSchedule>,
+    buyer_ogun_account: Option<&TokenAccount>,
+    buyer_stake_account: Option<&StakeAccount>,
+    buyer: Pubkey,
+) -> Result<(u16, FeeDiscount)> {
+    let Some(fee_schedule) = fee_schedule else {
+        return Ok((fee_bps, FeeDiscount::None));
+    };
+
+    let mut discount = (FeeDiscount::None, 0);
+    if let Some(ogun_account) = buyer_ogun_account {
+        require_keys_eq!(ogun_account.mint, fee_schedule.ogun_mint, ErrorCode::InvalidOgunAccount);
+        if fee_schedule.holder_discount > 0 && ogun_account.amount >= fee_schedule.holder_threshold {
+            discount = (FeeDiscount::Holder, fee_schedule.holder_discount);
+        }
+    }
+    if let Some(stake_account) = buyer_stake_account {
+        require_keys_eq!(stake_account.owner, buyer, ErrorCode::InvalidStakeAccount);
+        require_keys_eq!(stake_account.pool, fee_schedule.staking_pool, ErrorCode::InvalidStakeAccount);
+        if fee_schedule.staker_discount > discount.1
+            && stake_account.staked_amount >= fee_schedule.staker_threshold
+        {
+            discount = (FeeDiscount::Staker, fee_schedule.staker_discount);
+        }
+    }
+
+    let reduction = (fee_bps as u32 * discount.1 as u32 / 10000) as u16;
+    Ok((fee_bps - reduction, discount.0))
+}
+
+/// Leading fields of a Metaplex metadata account, up to the collection
+#[derive(AnchorDeserialize)]
+struct MetadataPrefix {
+    _key: u8,
+    _update_authority: Pubkey,
+    _mint: Pubkey,
+    _name: String,
+    _symbol: String,
+    _uri: String,
+    _seller_fee_basis_points: u16,
+    _creators: Option<Vec<MetadataCreator>>,
+    _primary_sale_happened: bool,
+    _is_mutable: bool,
+    _edition_nonce: Option<u8>,
+    _token_standard: Option<u8>,
+    collection: Option<MetadataCollection>,
+}
+
+#[derive(AnchorDeserialize)]
+struct MetadataCreator {
+    _address: Pubkey,
+    _verified: bool,
+    _share: u8,
+}
+
+#[derive(AnchorDeserialize)]
+struct MetadataCollection {
+    verified: bool,
+    key: Pubkey,
+}
+
+/// Verified collection of an NFT from its metadata account
+fn verified_collection(metadata: &AccountInfo) -> Result<Pubkey> {
+    let data = metadata.try_borrow_data()?;
+    let metadata = MetadataPrefix::deserialize(&mut &data[..])
+        .map_err(|_| error!(ErrorCode::InvalidMetadata))?;
+    metadata
+        .collection
+        .filter(|collection| collection.verified)
+        .map(|collection| collection.key)
+        .ok_or(error!(ErrorCode::CollectionNotVerified))
+}
+
+/// Check `holder` passes the listing's token gate with the accounts they provided
+fn check_token_gate(
+    listing: &Listing,
+    holder: &Pubkey,
+    gate_token_account: Option<&Account<TokenAccount>>,
+    gate_nft_metadata: Option<&UncheckedAccount>,
+) -> Result<()> {
+    let Some(token_gate) = &listing.token_gate else {
+        return Ok(());
+    };
+    let token_account = gate_token_account.ok_or(ErrorCode::TokenGateNotMet)?;
+    require_keys_eq!(token_account.owner, *holder, ErrorCode::TokenGateNotMet);
+
+    match token_gate {
+        TokenGate::MinBalance { mint, amount } => {
+            require_keys_eq!(token_account.mint, *mint, ErrorCode::TokenGateNotMet);
+            require!(token_account.amount >= *amount, ErrorCode::TokenGateNotMet);
+        }
+        TokenGate::Collection(collection) => {
+            require!(token_account.amount > 0, ErrorCode::TokenGateNotMet);
+            let metadata = gate_nft_metadata.ok_or(ErrorCode::MetadataRequired)?;
+            let (expected, _) = Pubkey::find_program_address(
+                &[b"metadata", token_metadata::ID.as_ref(), token_account.mint.as_ref()],
+                &token_metadata::ID,
+            );
+            require_keys_eq!(metadata.key(), expected, ErrorCode::InvalidMetadata);
+            require_keys_eq!(verified_collection(metadata)?, *collection, ErrorCode::TokenGateNotMet);
+        }
+    }
+    Ok(())
+}
+
+/// Require an auction listing to have no bids yet
+fn require_no_bids(listing: &Listing, auction: Option<&Account<Auction>>) -> Result<()> {
+    if listing.listing_type == ListingType::Auction {
+        let auction = auction.ok_or(ErrorCode::NotAuction)?;
+        require!(auction.current_bid == 0, ErrorCode::HasActiveBid);
+    }
+    Ok(())
+}
+
+/// Fields of a Pyth receiver `PriceUpdateV2` account after the discriminator
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PythPriceUpdate {
+    pub write_authority: Pubkey,
+    pub verification_level: PythVerificationLevel,
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub prev_publish_time: i64,
+    pub ema_price: i64,
+    pub ema_conf: u64,
+    pub posted_slot: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PythVerificationLevel {
+    Partial { num_signatures: u8 },
+    Full,
+}
+
+impl PythPriceUpdate {
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let payload = data
+            .strip_prefix(&PYTH_PRICE_UPDATE_DISCRIMINATOR[..])
+            .ok_or(ErrorCode::InvalidPriceOracle)?;
+        PythPriceUpdate::deserialize(&mut &payload[..]).map_err(|_| error!(ErrorCode::InvalidPriceOracle))
+    }
+}
+
+/// Payment token amount for `usd_price` micro-dollars at the oracle price,
+/// rounded up. Rejects other feeds' updates and partially verified, stale or
+/// low-confidence prices.
+pub fn usd_to_tokens(usd_price: u64, update: &PythPriceUpdate, price_feed: &PriceFeed, now: i64) -> Result<u64> {
+    require!(update.feed_id == price_feed.feed_id, ErrorCode::OracleFeedMismatch);
+    require!(
+        update.verification_level == PythVerificationLevel::Full && update.price > 0,
+        ErrorCode::InvalidPriceOracle
+    );
+    require!(
+        now.saturating_sub(update.publish_time) <= price_feed.max_staleness,
+        ErrorCode::StaleOraclePrice
+    );
+    let price = update.price as u128;
+    require!(
+        update.conf as u128 * 10000 <= price * price_feed.max_confidence as u128,
+        ErrorCode::OracleConfidenceTooWide
+    );
+
+    // tokens = usd_price / 10^USD_DECIMALS / (price * 10^exponent) * 10^decimals
+    let scale = price_feed.decimals as i32 - USD_DECIMALS as i32 - update.exponent;
+    let factor = 10u128.checked_pow(scale.unsigned_abs()).ok_or(ErrorCode::PriceOverflow)?;
+    let (numerator, denominator) = if scale >= 0 {
+        ((usd_price as u128).checked_mul(factor), Some(price))
+    } else {
+        (Some(usd_price as u128), price.checked_mul(factor))
+    };
+    let (numerator, denominator) = numerator.zip(denominator).ok_or(ErrorCode::PriceOverflow)?;
+    u64::try_from(numerator.div_ceil(denominator)).map_err(|_| error!(ErrorCode::PriceOverflow))
+}
+
+/// Count a new listing in the global, seller and collection stats
+fn record_listing(accounts: &mut CreateListing) -> Result<()> {
+    let seller = accounts.seller.key();
+    let payment_mint = accounts.payment_mint.key();
+    accounts.marketplace.total_listings += 1;
+    accounts.seller_stats.record_listing(seller, payment_mint);
+
+    if let Some(collection_stats) = accounts.collection_stats.as_mut() {
+        verify_collection_stats(collection_stats, accounts.nft_metadata.as_ref(), payment_mint)?;
+        let collection = collection_stats.key;
+        collection_stats.record_listing(collection, payment_mint);
+    }
+
+    Ok(())
+}
+
+/// Optional account in a sweep group; the program id stands in for an omitted one
+fn sweep_optional<'info>(account: &'info AccountInfo<'info>, program_id: &Pubkey) -> Option<&'info AccountInfo<'info>> {
+    (account.key != program_id).then_some(account)
+}
+
+/// Record a sale on the collection stats account when one is passed
+fn record_collection_sale(
+    collection_stats: Option<&mut Account<SalesStats>>,
+    nft_metadata: Option<&UncheckedAccount>,
+    payment_mint: Pubkey,
+    price: u64,
+    now: i64,
+) -> Result<()> {
+    let Some(collection_stats) = collection_stats else {
+        return Ok(());
+    };
+    verify_collection_stats(collection_stats, nft_metadata, payment_mint)?;
+    let collection = collection_stats.key;
+    collection_stats.record_sale(collection, payment_mint, price, now);
+    Ok(())
+}
+
+/// Check collection stats match the NFT's verified collection and the payment mint
+fn verify_collection_stats(
+    collection_stats: &SalesStats,
+    nft_metadata: Option<&UncheckedAccount>,
+    payment_mint: Pubkey,
+) -> Result<()> {
+    let metadata = nft_metadata.ok_or(ErrorCode::MetadataRequired)?;
+    let collection = verified_collection(metadata)?;
+    require_keys_eq!(collection, collection_stats.key, ErrorCode::CollectionMismatch);
+    require_keys_eq!(payment_mint, collection_stats.payment_mint, ErrorCode::InvalidStatsAccount);
+    Ok(())
+}
+
+/// Require a role assignment to grant `role`
+fn require_role(role_assignment: &RoleAssignment, role: Role) -> Result<()> {
+    require!(role_assignment.role == role, ErrorCode::MissingRole);
+    Ok(())
+}
+
+/// Grow a program account to `space`, topping up rent from `payer`, and rewrite it
+/// with `upgrade` applied. Bytes past the serialized data are zeroed so fields
+/// appended by later layouts start out empty.
+fn migrate_account<'info, T>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+    upgrade: impl FnOnce(&mut T),
+) -> Result<()>
+where
+    T: AccountSerialize + AccountDeserialize + Owner,
+{
+    require_keys_eq!(*account.owner, T::owner(), ErrorCode::InvalidMigrationAccount);
+
+    if account.data_len() < space {
+        let top_up = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+        if top_up > 0 {
+            let cpi_context = CpiContext::new(
+                system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            );
+            anchor_lang::system_program::transfer(cpi_context, top_up)?;
+        }
+        account.realloc(space, true)?;
+    }
+
+    // Checks the discriminator; legacy layouts read the new trailing fields as zero
+    let mut data = T::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    upgrade(&mut data);
+
+    let mut buf = Vec::with_capacity(space);
+    data.try_serialize(&mut buf)?;
+    let mut dst = account.try_borrow_mut_data()?;
+    dst[..buf.len()].copy_from_slice(&buf);
+    dst[buf.len()..].fill(0);
+    Ok(())
+}
+
+/// Approved referrer and the token account that receives their share
+struct Referral<'a, 'info> {
+    wallet: Pubkey,
+    payment_account: &'a Account<'info, TokenAccount>,
+}
+
+impl<'a, 'info> Referral<'a, 'info> {
+    /// Validate an optional referrer passed to a sale
+    fn resolve(
+        referrer: Option<&Account<'info, Referrer>>,
+        payment_account: Option<&'a Account<'info, TokenAccount>>,
+        payment_mint: &Pubkey,
+    ) -> Result<Option<Self>> {
+        let Some(referrer) = referrer else {
+            return Ok(None);
+        };
+        require!(referrer.approved, ErrorCode::ReferrerNotApproved);
+        let referral = Self::for_wallet(referrer.wallet, payment_account, payment_mint)?;
+        Ok(Some(referral))
+    }
+
+    /// Check the payment account belongs to `wallet` and is in the sale mint
+    fn for_wallet(
+        wallet: Pubkey,
+        payment_account: Option<&'a Account<'info, TokenAccount>>,
+        payment_mint: &Pubkey,
+    ) -> Result<Self> {
+        let payment_account = payment_account.ok_or(ErrorCode::ReferrerAccountRequired)?;
+        require_keys_eq!(payment_account.owner, wallet, ErrorCode::InvalidReferrerAccount);
+        require_keys_eq!(payment_account.mint, *payment_mint, ErrorCode::InvalidReferrerAccount);
+        Ok(Self {
+            wallet,
+            payment_account,
+        })
+    }
+
+    /// Pay the referrer's share of `platform_fee` from `from`; returns the share
+    #[allow(clippy::too_many_arguments)]
+    fn pay(
+        &self,
+        marketplace: &Marketplace,
+        listing: Pubkey,
+        platform_fee: u64,
+        from: &Account<'info, TokenAccount>,
+        authority: AccountInfo<'info>,
+        signer: &[&[&[u8]]],
+        token_program: &Program<'info, Token>,
+    ) -> Result<u64> {
+        let amount = (platform_fee as u128 * marketplace.referral_share as u128 / 10000) as u64;
+        if amount == 0 {
+            return Ok(0);
+        }
+
+        let cpi_accounts = TokenTransfer {
+            from: from.to_account_info(),
+            to: self.payment_account.to_account_info(),
+            authority,
+        };
+        let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts).with_signer(signer);
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(ReferralPaid {
+            referrer: self.wallet,
+            listing,
+            payment_mint: from.mint,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(amount)
+    }
+}
+
+/// Record a fixed price sale on the listing and emit `ListingSold`
+fn mark_sold(listing: &mut Account<Listing>, buyer: Pubkey, platform_fee: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    listing.status = ListingStatus::Sold;
+    listing.buyer = Some(buyer);
+    listing.sold_at = Some(now);
+
+    emit!(ListingSold {
+        listing: listing.key(),
+        seller: listing.seller,
+        buyer,
+        nft_mint: listing.nft_mint,
+        price: listing.price,
+        platform_fee,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Pay the same OGUN reward to seller and buyer from the rewards vault.
+/// Skipped when the vault cannot cover both, like Marketplace.sol.
+fn pay_trading_rewards<'info>(
+    marketplace: &Account<'info, Marketplace>,
+    marketplace_bump: u8,
+    rewards_vault: &Account<'info, TokenAccount>,
+    seller_account: &Account<'info, TokenAccount>,
+    buyer_account: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    reward: u64,
+) -> Result<()> {
+    if rewards_vault.amount < reward.saturating_mul(2) {
+        return Ok(());
+    }
+
+    let seeds = &[b"marketplace".as_ref(), &[marketplace_bump]];
+    let signer = &[&seeds[..]];
+
+    for recipient in [seller_account, buyer_account] {
+        let cpi_accounts = TokenTransfer {
+            from: rewards_vault.to_account_info(),
+            to: recipient.to_account_info(),
+            authority: marketplace.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, reward)?;
+    }
+
+    emit!(TradingRewardsPaid {
+        seller: seller_account.owner,
+        buyer: buyer_account.owner,
+        amount: reward,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// ============ Enums ============
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ListingType {
+    FixedPrice,
+    Auction,
+    MakeOffer,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ListingStatus {
+    Active,
+    Sold,
+    Cancelled,
+    Expired,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SweepMode {
+    AllOrNothing,
+    SkipUnavailable,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FeeDiscount {
+    None,
+    Holder,
+    Staker,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Pauser,
+    FeeManager,
+    TreasuryManager,
+    CrossChainVerifier,
+    Moderator,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum CrossChainStatus {
+    Completed,
+    Failed,
+    Refunded,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct MintPrice {
+    pub mint: Pubkey,
+    pub price: u64,
+}
+
+/// Who may buy or bid on a listing
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BuyerRestriction {
+    /// Reserved for one wallet
+    Buyer(Pubkey),
+    /// Wallets in a Merkle tree whose leaves are `keccak(wallet)` and whose
+    /// nodes hash the sorted pair of children
+    Allowlist { root: [u8; 32] },
+}
+
+impl BuyerRestriction {
+    pub fn allows(&self, buyer: &Pubkey, proof: &[[u8; 32]]) -> bool {
+        match self {
+            BuyerRestriction::Buyer(allowed) => allowed == buyer,
+            BuyerRestriction::Allowlist { root } => allowlist_root(buyer, proof) == *root,
+        }
+    }
+}
+
+/// Token buyers and bidders must hold
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TokenGate {
+    /// At least `amount` of `mint`, e.g. OGUN
+    MinBalance { mint: Pubkey, amount: u64 },
+    /// An NFT from a verified collection
+    Collection(Pubkey),
+}
+
+/// Merkle root implied by `buyer` and its proof
+pub fn allowlist_root(buyer: &Pubkey, proof: &[[u8; 32]]) -> [u8; 32] {
+    proof.iter().fold(keccak::hash(buyer.as_ref()).0, |node, sibling| {
+        let (left, right) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+        keccak::hashv(&[&left, &right]).0
+    })
+}
+
+/// Purchase message sent through the ZetaChain gateway
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CrossChainPurchase {
+    pub route_id: [u8; 32],
+    pub source_chain: u64,
+    pub listing: Pubkey,
+    pub recipient: Pubkey,
+}
+
+// ============ Accounts ============
+
+#[account]
+pub struct Marketplace {
+    pub authority: Pubkey,
+    pub fee_collector: Pubkey,
+    pub platform_fee: u16,
+    pub total_listings: u64,
+    pub total_sales: u64,
+    pub paused: bool,
+    pub rewards_mint: Pubkey,
+    pub rewards_vault: Pubkey,
+    pub rewards_rate: u16,  // Basis points of sale price
+    pub rewards_limit: u64, // Max reward per party per sale
+    pub pending_authority: Option<Pubkey>,
+    pub pending_fee: Option<u16>,
+    pub fee_change_eta: i64, // When pending_fee can be executed
+    pub fee_delay: i64,      // Fee change timelock in seconds
+    pub referral_share: u16, // Basis points of the platform fee paid to referrers
+    pub version: u8,         // New fields go after this
+}
+
+impl Marketplace {
+    /// Bring a migrated account to the current layout
+    ///
+    /// Accounts created before the fee timelock read `fee_delay` as 0, which
+    /// would let a proposed fee apply immediately.
+    pub fn upgrade(&mut self) {
+        self.version = MARKETPLACE_VERSION;
+        if self.fee_delay == 0 {
+            self.fee_delay = DEFAULT_FEE_DELAY;
+        }
+    }
+
+    /// Queue `new_fee` to apply once `fee_delay` has passed
+    pub fn propose_fee(&mut self, new_fee: u16, now: i64) {
+        self.pending_fee = Some(new_fee);
+        self.fee_change_eta = now + self.fee_delay;
+    }
+
+    /// Apply the pending fee if its timelock has passed, returning it
+    pub fn execute_fee(&mut self, now: i64) -> Result<u16> {
+        let new_fee = self.pending_fee.ok_or(ErrorCode::NoPendingFee)?;
+        require!(now >= self.fee_change_eta, ErrorCode::TimelockActive);
+
+        self.platform_fee = new_fee;
+        self.pending_fee = None;
+        self.fee_change_eta = 0;
+        Ok(new_fee)
+    }
+
+    /// OGUN reward per party for a sale; only sales paid in the rewards mint earn rewards
+    pub fn trading_reward(&self, listing: &Listing, price: u64) -> u64 {
+        if self.rewards_rate == 0
+            || self.rewards_mint == Pubkey::default()
+            || listing.payment_mint != self.rewards_mint
+        {
+            return 0;
+        }
+        let reward = (price as u128 * self.rewards_rate as u128 / 10000) as u64;
+        reward.min(self.rewards_limit)
+    }
+}
+
+#[account]
+pub struct Listing {
+    pub seller: Pubkey,
+    pub nft_mint: Pubkey,
+    pub payment_mint: Pubkey,
+    pub price: u64,
+    pub listing_type: ListingType,
+    pub status: ListingStatus,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub scid: Option<String>,
+    pub buyer: Option<Pubkey>,
+    pub sold_at: Option<i64>,
+    pub version: u8,            // New fields go after this
+    pub usd_price: Option<u64>, // USD listings: micro-dollars; `price` is set when bought
+    pub restriction: Option<BuyerRestriction>,
+    pub token_gate: Option<TokenGate>,
+}
+
+impl Listing {
+    /// Whether `buyer` may buy or bid, given their allowlist proof if any
+    pub fn buyer_allowed(&self, buyer: &Pubkey, proof: &[[u8; 32]]) -> bool {
+        self.restriction.as_ref().map_or(true, |restriction| restriction.allows(buyer, proof))
+    }
+}
+
+#[account]
+pub struct Auction {
+    pub listing: Pubkey,
+    pub current_bid: u64,
+    pub current_bidder: Pubkey,
+    pub bid_count: u32,
+    pub reserve_met: bool,
+    pub referrer: Option<Pubkey>,
+    pub version: u8, // New fields go after this
+}
+
+#[account]
+pub struct FeeSchedule {
+    pub ogun_mint: Pubkey,
+    pub staking_pool: Pubkey,    // soundchain-staking pool for ogun_mint
+    pub holder_threshold: u64,   // Min OGUN balance for the holder discount
+    pub holder_discount: u16,    // Basis points off the platform fee
+    pub staker_threshold: u64,   // Min OGUN staked for the staker discount
+    pub staker_discount: u16,    // Basis points off the platform fee
+}
 
 #[account]
-pub struct Marketplace {
-    pub authority: Pubkey,
-    pub fee_collector: Pubkey,
-    pub platform_fee: u16,
-    pub total_listings: u64,
-    pub total_sales: u64,
-    pub paused: bool,
+pub struct CollectionFee {
+    pub collection: Pubkey,
+    pub fee: u16,
+}
+
+#[account]
+pub struct PartnerFee {
+    pub seller: Pubkey,
+    pub fee: u16,
+}
+
+/// Listing and sales stats for a seller (`[b"seller_stats", seller, payment_mint]`)
+/// or a verified collection (`[b"collection_stats", collection, payment_mint]`)
+#[account]
+pub struct SalesStats {
+    pub key: Pubkey, // Seller or collection
+    pub payment_mint: Pubkey,
+    pub listing_count: u64,
+    pub sales_count: u64,
+    pub volume: u128,
+    pub last_sale_price: u64,
+    pub last_sale_at: i64,
+    pub highest_sale: u64,
+}
+
+impl SalesStats {
+    pub fn record_listing(&mut self, key: Pubkey, payment_mint: Pubkey) {
+        self.key = key;
+        self.payment_mint = payment_mint;
+        self.listing_count += 1;
+    }
+
+    pub fn record_sale(&mut self, key: Pubkey, payment_mint: Pubkey, price: u64, now: i64) {
+        self.key = key;
+        self.payment_mint = payment_mint;
+        self.sales_count += 1;
+        self.volume += price as u128;
+        self.last_sale_price = price;
+        self.last_sale_at = now;
+        self.highest_sale = self.highest_sale.max(price);
+    }
+}
+
+/// Payment mints a listing accepts besides its own, with their prices
+#[account]
+pub struct ListingPrices {
+    pub listing: Pubkey,
+    pub prices: Vec<MintPrice>,
+}
+
+impl ListingPrices {
+    pub fn price_for(&self, mint: &Pubkey) -> Option<u64> {
+        self.prices.iter().find(|price| price.mint == *mint).map(|price| price.price)
+    }
+}
+
+/// Payment mint accepted for USD listings, and the oracle that prices it
+#[account]
+pub struct PriceFeed {
+    pub payment_mint: Pubkey,
+    pub oracle: Pubkey,      // Pyth price update account, USD per token
+    pub decimals: u8,        // Payment mint decimals
+    pub max_staleness: i64,  // Max oracle price age in seconds
+    pub max_confidence: u16, // Max confidence interval, basis points of the price
+    pub feed_id: [u8; 32],   // Pyth feed the oracle must carry
+}
+
+#[account]
+pub struct Referrer {
+    pub wallet: Pubkey,
+    pub approved: bool,
+    pub registered_at: i64,
+}
+
+#[account]
+pub struct RoleAssignment {
+    pub role: Role,
+    pub holder: Pubkey,
+}
+
+#[account]
+pub struct CrossChainConfig {
+    pub gateway: Pubkey, // ZetaChain gateway signer allowed to call on_call/on_revert
+}
+
+#[account]
+pub struct CrossChainReceipt {
+    pub route_id: [u8; 32],
+    pub source_chain: u64,
+    pub sender: [u8; 20],
+    pub listing: Pubkey,
+    pub recipient: Pubkey,
+    pub payment_mint: Pubkey,
+    pub amount: u64,
+    pub status: CrossChainStatus,
+    pub created_at: i64,
+}
+
+// ============ Contexts ============
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = MARKETPLACE_SPACE,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateListing<'info> {
+    #[account(mut, seeds = [b"marketplace"], bump)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(
+        init,
+        payer = seller,
+        space = LISTING_SPACE,
+        seeds = [b"listing", nft_mint.key().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+    pub nft_mint: Account<'info, token::Mint>,
+    pub payment_mint: Account<'info, token::Mint>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(mut)]
+    pub seller_nft_account: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::mint = nft_mint,
+        associated_token::authority = listing
+    )]
+    pub escrow_nft_account: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = SALES_STATS_SPACE,
+        seeds = [b"seller_stats", seller.key().as_ref(), payment_mint.key().as_ref()],
+        bump
+    )]
+    pub seller_stats: Account<'info, SalesStats>,
+    /// CHECK: Metaplex metadata of the NFT, decoded for its collection
+    #[account(
+        seeds = [b"metadata", token_metadata::ID.as_ref(), nft_mint.key().as_ref()],
+        bump,
+        seeds::program = token_metadata::ID,
+        owner = token_metadata::ID
+    )]
+    pub nft_metadata: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [b"collection_stats", collection_stats.key.as_ref(), collection_stats.payment_mint.as_ref()],
+        bump
+    )]
+    pub collection_stats: Option<Account<'info, SalesStats>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    /// Required for USD listings
+    #[account(seeds = [b"price_feed", payment_mint.key().as_ref()], bump)]
+    pub price_feed: Option<Account<'info, PriceFeed>>,
+}
+
+#[derive(Accounts)]
+pub struct Buy<'info> {
+    #[account(mut, seeds = [b"marketplace"], bump)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(
+        mut,
+        seeds = [b"listing", listing.nft_mint.as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    /// Its mint is the one paid in; checked against the listing in `buy`
+    #[account(mut, token::authority = buyer)]
+    pub buyer_payment_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = listing.nft_mint, token::authority = buyer)]
+    pub buyer_nft_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = buyer_payment_account.mint, token::authority = listing.seller)]
+    pub seller_payment_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = buyer_payment_account.mint,
+        associated_token::authority = marketplace.fee_collector
+    )]
+    pub fee_collector_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = listing.nft_mint,
+        associated_token::authority = listing
+    )]
+    pub escrow_nft_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    #[account(mut, address = marketplace.rewards_vault)]
+    pub rewards_vault: Option<Account<'info, TokenAccount>>,
+    #[account(seeds = [b"fee_schedule"], bump)]
+    pub fee_schedule: Option<Account<'info, FeeSchedule>>,
+    #[account(seeds = [b"collection_fee", collection_fee.collection.as_ref()], bump)]
+    pub collection_fee: Option<Account<'info, CollectionFee>>,
+    /// CHECK: Metaplex metadata of the listed NFT, decoded for its collection
+    #[account(
+        seeds = [b"metadata", token_metadata::ID.as_ref(), listing.nft_mint.as_ref()],
+        bump,
+        seeds::program = token_metadata::ID,
+        owner = token_metadata::ID
+    )]
+    pub nft_metadata: Option<UncheckedAccount<'info>>,
+    #[account(seeds = [b"partner_fee", listing.seller.as_ref()], bump)]
+    pub partner_fee: Option<Account<'info, PartnerFee>>,
+    #[account(token::authority = buyer)]
+    pub buyer_ogun_account: Option<Account<'info, TokenAccount>>,
+    pub buyer_stake_account: Option<Account<'info, StakeAccount>>,
+    #[account(seeds = [b"referrer", referrer.wallet.as_ref()], bump)]
+    pub referrer: Option<Account<'info, Referrer>>,
+    #[account(mut)]
+    pub referrer_payment_account: Option<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = SALES_STATS_SPACE,
+        seeds = [b"seller_stats", listing.seller.as_ref(), buyer_payment_account.mint.as_ref()],
+        bump
+    )]
+    pub seller_stats: Account<'info, SalesStats>,
+    #[account(
+        mut,
+        seeds = [b"collection_stats", collection_stats.key.as_ref(), collection_stats.payment_mint.as_ref()],
+        bump
+    )]
+    pub collection_stats: Option<Account<'info, SalesStats>>,
+    pub system_program: Program<'info, System>,
+    /// Required for USD listings; the feed of the mint the buyer pays in
+    #[account(seeds = [b"price_feed", buyer_payment_account.mint.as_ref()], bump)]
+    pub price_feed: Option<Account<'info, PriceFeed>>,
+    /// CHECK: Pyth price update account, checked against `price_feed.oracle`
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+    /// Required to pay in a mint other than the listing's `payment_mint`
+    #[account(seeds = [b"listing_prices", listing.key().as_ref()], bump)]
+    pub listing_prices: Option<Account<'info, ListingPrices>>,
+    /// CHECK: Swap program chosen by the buyer for `buy_with_swap`; it only gets the buyer's signature
+    #[account(executable)]
+    pub swap_program: Option<UncheckedAccount<'info>>,
+    /// Token account `buy_with_swap` pays from
+    #[account(mut, token::authority = buyer)]
+    pub swap_source_account: Option<Account<'info, TokenAccount>>,
+    /// Buyer's token account holding the listing's `token_gate` token
+    pub gate_token_account: Option<Account<'info, TokenAccount>>,
+    /// CHECK: Metaplex metadata of the gate NFT, for collection gates; checked in `check_token_gate`
+    #[account(owner = token_metadata::ID)]
+    pub gate_nft_metadata: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct Sweep<'info> {
+    #[account(mut, seeds = [b"marketplace"], bump)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(mut, token::authority = buyer)]
+    pub buyer_payment_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = buyer_payment_account.mint,
+        associated_token::authority = marketplace.fee_collector
+    )]
+    pub fee_collector_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    #[account(mut, address = marketplace.rewards_vault)]
+    pub rewards_vault: Option<Account<'info, TokenAccount>>,
+    #[account(seeds = [b"fee_schedule"], bump)]
+    pub fee_schedule: Option<Account<'info, FeeSchedule>>,
+    #[account(token::authority = buyer)]
+    pub buyer_ogun_account: Option<Account<'info, TokenAccount>>,
+    pub buyer_stake_account: Option<Account<'info, StakeAccount>>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, sender: [u8; 20], message: CrossChainPurchase)]
+pub struct OnCall<'info> {
+    #[account(mut, seeds = [b"marketplace"], bump)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(seeds = [b"cross_chain"], bump, has_one = gateway)]
+    pub cross_chain_config: Account<'info, CrossChainConfig>,
+    pub gateway: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 8 + 20 + 32 + 32 + 32 + 8 + 1 + 8,
+        seeds = [b"cross_chain_receipt", message.route_id.as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, CrossChainReceipt>,
+    #[account(
+        mut,
+        address = message.listing,
+        seeds = [b"listing", listing.nft_mint.as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+    #[account(address = listing.nft_mint)]
+    pub nft_mint: Account<'info, token::Mint>,
+    #[account(address = listing.payment_mint)]
+    pub payment_mint: Account<'info, token::Mint>,
+    /// CHECK: Solana recipient named in the cross-chain message
+    #[account(address = message.recipient)]
+    pub recipient: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = cross_chain_config
+    )]
+    pub payment_vault: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = nft_mint,
+        associated_token::authority = recipient
+    )]
+    pub recipient_nft_account: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = payment_mint,
+        associated_token::authority = recipient
+    )]
+    pub recipient_payment_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = payment_mint, token::authority = listing.seller)]
+    pub seller_payment_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = marketplace.fee_collector
+    )]
+    pub fee_collector_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = listing
+    )]
+    pub escrow_nft_account: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = SALES_STATS_SPACE,
+        seeds = [b"seller_stats", listing.seller.as_ref(), listing.payment_mint.as_ref()],
+        bump
+    )]
+    pub seller_stats: Account<'info, SalesStats>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    #[account(mut, address = marketplace.rewards_vault)]
+    pub rewards_vault: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+#[instruction(route_id: [u8; 32])]
+pub struct OnRevert<'info> {
+    #[account(seeds = [b"cross_chain"], bump, has_one = gateway)]
+    pub cross_chain_config: Account<'info, CrossChainConfig>,
+    pub gateway: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"cross_chain_receipt", route_id.as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, CrossChainReceipt>,
+    #[account(address = receipt.payment_mint)]
+    pub payment_mint: Account<'info, token::Mint>,
+    /// CHECK: Solana recipient recorded on the receipt
+    #[account(address = receipt.recipient)]
+    pub recipient: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = cross_chain_config
+    )]
+    pub payment_vault: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = payment_mint,
+        associated_token::authority = recipient
+    )]
+    pub recipient_payment_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceBid<'info> {
+    #[account(seeds = [b"marketplace"], bump)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(seeds = [b"listing", listing.nft_mint.as_ref()], bump)]
+    pub listing: Account<'info, Listing>,
+    #[account(
+        mut,
+        seeds = [b"auction", listing.key().as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(mut, token::mint = listing.payment_mint, token::authority = bidder)]
+    pub bidder_payment_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = listing.payment_mint,
+        associated_token::authority = auction
+    )]
+    pub escrow_payment_account: Account<'info, TokenAccount>,
+    /// Previous bidder's token account for the refund; only checked against
+    /// the bidder once there is a bid to refund
+    #[account(
+        mut,
+        token::mint = listing.payment_mint,
+        constraint = auction.current_bid == 0
+            || previous_bidder_account.owner == auction.current_bidder @ ErrorCode::InvalidPreviousBidderAccount
+    )]
+    pub previous_bidder_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    #[account(seeds = [b"referrer", referrer.wallet.as_ref()], bump)]
+    pub referrer: Option<Account<'info, Referrer>>,
+    /// Bidder's token account holding the listing's `token_gate` token
+    pub gate_token_account: Option<Account<'info, TokenAccount>>,
+    /// CHECK: Metaplex metadata of the gate NFT, for collection gates; checked in `check_token_gate`
+    #[account(owner = token_metadata::ID)]
+    pub gate_nft_metadata: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    #[account(mut, seeds = [b"marketplace"], bump)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(
+        mut,
+        seeds = [b"listing", listing.nft_mint.as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+    #[account(
+        seeds = [b"auction", listing.key().as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, Auction>,
+    #[account(mut, token::mint = listing.payment_mint, token::authority = listing.seller)]
+    pub seller_payment_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = listing.nft_mint, token::authority = listing.seller)]
+    pub seller_nft_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = listing.payment_mint,
+        associated_token::authority = marketplace.fee_collector
+    )]
+    pub fee_collector_account: Account<'info, TokenAccount>,
+    /// Only checked against the winner when the auction succeeds
+    #[account(
+        mut,
+        token::mint = listing.nft_mint,
+        constraint = !auction.reserve_met
+            || winner_nft_account.owner == auction.current_bidder @ ErrorCode::InvalidWinnerAccount
+    )]
+    pub winner_nft_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = listing.nft_mint,
+        associated_token::authority = listing
+    )]
+    pub escrow_nft_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = listing.payment_mint, token::authority = auction)]
+    pub escrow_payment_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    #[account(mut, address = marketplace.rewards_vault)]
+    pub rewards_vault: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = marketplace.rewards_mint,
+        token::authority = auction.current_bidder
+    )]
+    pub winner_rewards_account: Option<Account<'info, TokenAccount>>,
+    /// Required when the winning bid has a referrer
+    #[account(mut)]
+    pub referrer_payment_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = SALES_STATS_SPACE,
+        seeds = [b"seller_stats", listing.seller.as_ref(), listing.payment_mint.as_ref()],
+        bump
+    )]
+    pub seller_stats: Account<'info, SalesStats>,
+    /// CHECK: Metaplex metadata of the listed NFT, decoded for its collection
+    #[account(
+        seeds = [b"metadata", token_metadata::ID.as_ref(), listing.nft_mint.as_ref()],
+        bump,
+        seeds::program = token_metadata::ID,
+        owner = token_metadata::ID
+    )]
+    pub nft_metadata: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [b"collection_stats", collection_stats.key.as_ref(), collection_stats.payment_mint.as_ref()],
+        bump
+    )]
+    pub collection_stats: Option<Account<'info, SalesStats>>,
+    pub system_program: Program<'info, System>,
 }
 
-#[account]
-pub struct Listing {
-    pub seller: Pubkey,
-    pub nft_mint: Pubkey,
-    pub payment_mint: Pubkey,
-    pub price: u64,
-    pub listing_type: ListingType,
-    pub status: ListingStatus,
-    pub created_at: i64,
-    pub expires_at: i64,
-    pub scid: Option<String>,
-    pub buyer: Option<Pubkey>,
-    pub sold_at: Option<i64>,
+#[derive(Accounts)]
+pub struct CancelListing<'info> {
+    #[account(
+        mut,
+        seeds = [b"listing", listing.nft_mint.as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+    /// CHECK: Optional auction account
+    #[account(seeds = [b"auction", listing.key().as_ref()], bump)]
+    pub auction: Option<Account<'info, Auction>>,
+    pub seller: Signer<'info>,
+    #[account(mut)]
+    pub seller_nft_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub escrow_nft_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireListing<'info> {
+    #[account(
+        mut,
+        seeds = [b"listing", listing.nft_mint.as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+    #[account(mut, token::mint = listing.nft_mint, token::authority = listing.seller)]
+    pub seller_nft_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = listing.nft_mint,
+        associated_token::authority = listing
+    )]
+    pub escrow_nft_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetListingPrices<'info> {
+    #[account(seeds = [b"listing", listing.nft_mint.as_ref()], bump, has_one = seller @ ErrorCode::NotSeller)]
+    pub listing: Account<'info, Listing>,
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = LISTING_PRICES_SPACE,
+        seeds = [b"listing_prices", listing.key().as_ref()],
+        bump
+    )]
+    pub listing_prices: Account<'info, ListingPrices>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetListingAccess<'info> {
+    #[account(
+        mut,
+        seeds = [b"listing", listing.nft_mint.as_ref()],
+        bump,
+        has_one = seller @ ErrorCode::NotSeller
+    )]
+    pub listing: Account<'info, Listing>,
+    pub seller: Signer<'info>,
+    /// Required to restrict or gate an auction
+    #[account(seeds = [b"auction", listing.key().as_ref()], bump)]
+    pub auction: Option<Account<'info, Auction>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseListingPrices<'info> {
+    #[account(has_one = seller @ ErrorCode::NotSeller)]
+    pub listing: Account<'info, Listing>,
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"listing_prices", listing.key().as_ref()],
+        bump
+    )]
+    pub listing_prices: Account<'info, ListingPrices>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdminAction<'info> {
+    #[account(mut, seeds = [b"marketplace"], bump, has_one = authority)]
+    pub marketplace: Account<'info, Marketplace>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateMarketplace<'info> {
+    /// CHECK: Legacy layouts may not deserialize; checked and rewritten in migrate_account
+    #[account(mut, seeds = [b"marketplace"], bump)]
+    pub marketplace: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// CHECK: Owner and discriminator are checked in migrate_account
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = marketplace.pending_authority == Some(pending_authority.key()) @ ErrorCode::NotPendingAuthority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    pub pending_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRewards<'info> {
+    #[account(mut, seeds = [b"marketplace"], bump, has_one = authority)]
+    pub marketplace: Account<'info, Marketplace>,
+    pub rewards_mint: Account<'info, token::Mint>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = rewards_mint,
+        associated_token::authority = marketplace
+    )]
+    pub rewards_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    #[account(seeds = [b"marketplace"], bump, has_one = rewards_vault)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut)]
+    pub rewards_vault: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [b"role", holder.key().as_ref(), &[role_assignment.role as u8]],
+        bump,
+        has_one = holder
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    pub holder: Signer<'info>,
+    #[account(mut)]
+    pub holder_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimRewards<'info> {
+    #[account(seeds = [b"marketplace"], bump, has_one = rewards_vault)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut)]
+    pub rewards_vault: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [b"role", holder.key().as_ref(), &[role_assignment.role as u8]],
+        bump,
+        has_one = holder
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    pub holder: Signer<'info>,
+    #[account(mut, token::mint = marketplace.rewards_mint)]
+    pub destination: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeCrossChain<'info> {
+    #[account(seeds = [b"marketplace"], bump, has_one = authority)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32,
+        seeds = [b"cross_chain"],
+        bump
+    )]
+    pub cross_chain_config: Account<'info, CrossChainConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetGateway<'info> {
+    #[account(mut, seeds = [b"cross_chain"], bump)]
+    pub cross_chain_config: Account<'info, CrossChainConfig>,
+    #[account(
+        seeds = [b"role", holder.key().as_ref(), &[role_assignment.role as u8]],
+        bump,
+        has_one = holder
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    pub holder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RoleAction<'info> {
+    #[account(mut, seeds = [b"marketplace"], bump)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(
+        seeds = [b"role", holder.key().as_ref(), &[role_assignment.role as u8]],
+        bump,
+        has_one = holder
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    pub holder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeSchedule<'info> {
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = 8 + 32 + 32 + 8 + 2 + 8 + 2,
+        seeds = [b"fee_schedule"],
+        bump
+    )]
+    pub fee_schedule: Account<'info, FeeSchedule>,
+    #[account(
+        seeds = [b"role", holder.key().as_ref(), &[role_assignment.role as u8]],
+        bump,
+        has_one = holder
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(collection: Pubkey)]
+pub struct SetCollectionFee<'info> {
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = 8 + 32 + 2,
+        seeds = [b"collection_fee", collection.as_ref()],
+        bump
+    )]
+    pub collection_fee: Account<'info, CollectionFee>,
+    #[account(
+        seeds = [b"role", holder.key().as_ref(), &[role_assignment.role as u8]],
+        bump,
+        has_one = holder
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveCollectionFee<'info> {
+    #[account(
+        mut,
+        close = holder,
+        seeds = [b"collection_fee", collection_fee.collection.as_ref()],
+        bump
+    )]
+    pub collection_fee: Account<'info, CollectionFee>,
+    #[account(
+        seeds = [b"role", holder.key().as_ref(), &[role_assignment.role as u8]],
+        bump,
+        has_one = holder
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    #[account(mut)]
+    pub holder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPriceFeed<'info> {
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = PRICE_FEED_SPACE,
+        seeds = [b"price_feed", payment_mint.key().as_ref()],
+        bump
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+    pub payment_mint: Account<'info, token::Mint>,
+    #[account(
+        seeds = [b"role", holder.key().as_ref(), &[role_assignment.role as u8]],
+        bump,
+        has_one = holder
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemovePriceFeed<'info> {
+    #[account(
+        mut,
+        close = holder,
+        seeds = [b"price_feed", price_feed.payment_mint.as_ref()],
+        bump
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+    #[account(
+        seeds = [b"role", holder.key().as_ref(), &[role_assignment.role as u8]],
+        bump,
+        has_one = holder
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    #[account(mut)]
+    pub holder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(seller: Pubkey)]
+pub struct SetPartnerFee<'info> {
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = 8 + 32 + 2,
+        seeds = [b"partner_fee", seller.as_ref()],
+        bump
+    )]
+    pub partner_fee: Account<'info, PartnerFee>,
+    #[account(
+        seeds = [b"role", holder.key().as_ref(), &[role_assignment.role as u8]],
+        bump,
+        has_one = holder
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
-#[account]
-pub struct Auction {
-    pub listing: Pubkey,
-    pub current_bid: u64,
-    pub current_bidder: Pubkey,
-    pub bid_count: u32,
-    pub reserve_met: bool,
+#[derive(Accounts)]
+pub struct RemovePartnerFee<'info> {
+    #[account(
+        mut,
+        close = holder,
+        seeds = [b"partner_fee", partner_fee.seller.as_ref()],
+        bump
+    )]
+    pub partner_fee: Account<'info, PartnerFee>,
+    #[account(
+        seeds = [b"role", holder.key().as_ref(), &[role_assignment.role as u8]],
+        bump,
+        has_one = holder
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    #[account(mut)]
+    pub holder: Signer<'info>,
 }
 
-// ============ Contexts ============
-
 #[derive(Accounts)]
-pub struct Initialize<'info> {
+#[instruction(seller: Pubkey, payment_mint: Pubkey)]
+pub struct InitSellerStats<'info> {
     #[account(
         init,
-        payer = authority,
-        space = 8 + 32 + 32 + 2 + 8 + 8 + 1,
-        seeds = [b"marketplace"],
+        payer = payer,
+        space = SALES_STATS_SPACE,
+        seeds = [b"seller_stats", seller.as_ref(), payment_mint.as_ref()],
         bump
     )]
-    pub marketplace: Account<'info, Marketplace>,
+    pub seller_stats: Account<'info, SalesStats>,
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct CreateListing<'info> {
-    #[account(seeds = [b"marketplace"], bump)]
-    pub marketplace: Account<'info, Marketplace>,
+#[instruction(collection: Pubkey, payment_mint: Pubkey)]
+pub struct InitCollectionStats<'info> {
     #[account(
         init,
-        payer = seller,
-        space = 8 + 32 + 32 + 32 + 8 + 1 + 1 + 8 + 8 + 36 + 33 + 9,
-        seeds = [b"listing", nft_mint.key().as_ref()],
+        payer = payer,
+        space = SALES_STATS_SPACE,
+        seeds = [b"collection_stats", collection.as_ref(), payment_mint.as_ref()],
         bump
     )]
-    pub listing: Account<'info, Listing>,
-    pub nft_mint: Account<'info, token::Mint>,
-    pub payment_mint: Account<'info, token::Mint>,
-    #[account(mut)]
-    pub seller: Signer<'info>,
+    pub collection_stats: Account<'info, SalesStats>,
     #[account(mut)]
-    pub seller_nft_account: Account<'info, TokenAccount>,
-    #[account(
-        init_if_needed,
-        payer = seller,
-        associated_token::mint = nft_mint,
-        associated_token::authority = listing
-    )]
-    pub escrow_nft_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct Buy<'info> {
-    #[account(seeds = [b"marketplace"], bump)]
-    pub marketplace: Account<'info, Marketplace>,
+pub struct RegisterReferrer<'info> {
     #[account(
-        mut,
-        seeds = [b"listing", listing.nft_mint.as_ref()],
+        init,
+        payer = wallet,
+        space = 8 + 32 + 1 + 8,
+        seeds = [b"referrer", wallet.key().as_ref()],
         bump
     )]
-    pub listing: Account<'info, Listing>,
-    #[account(mut)]
-    pub buyer: Signer<'info>,
-    #[account(mut)]
-    pub buyer_payment_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub buyer_nft_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub seller_payment_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub fee_collector_account: Account<'info, TokenAccount>,
+    pub referrer: Account<'info, Referrer>,
     #[account(mut)]
-    pub escrow_nft_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub wallet: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct PlaceBid<'info> {
-    #[account(seeds = [b"marketplace"], bump)]
+pub struct SetReferrerApproved<'info> {
+    #[account(seeds = [b"marketplace"], bump, has_one = authority)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut, seeds = [b"referrer", referrer.wallet.as_ref()], bump)]
+    pub referrer: Account<'info, Referrer>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(role: Role, holder: Pubkey)]
+pub struct GrantRole<'info> {
+    #[account(seeds = [b"marketplace"], bump, has_one = authority)]
     pub marketplace: Account<'info, Marketplace>,
-    #[account(seeds = [b"listing", listing.nft_mint.as_ref()], bump)]
-    pub listing: Account<'info, Listing>,
     #[account(
-        mut,
-        seeds = [b"auction", listing.key().as_ref()],
+        init,
+        payer = authority,
+        space = 8 + 1 + 32,
+        seeds = [b"role", holder.as_ref(), &[role as u8]],
         bump
     )]
-    pub auction: Account<'info, Auction>,
-    #[account(mut)]
-    pub bidder: Signer<'info>,
+    pub role_assignment: Account<'info, RoleAssignment>,
     #[account(mut)]
-    pub bidder_payment_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub escrow_payment_account: Account<'info, TokenAccount>,
-    /// CHECK: Previous bidder's token account for refund
-    #[account(mut)]
-    pub previous_bidder_account: AccountInfo<'info>,
-    pub token_program: Program<'info, Token>,
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct SettleAuction<'info> {
-    #[account(seeds = [b"marketplace"], bump)]
+pub struct RevokeRole<'info> {
+    #[account(seeds = [b"marketplace"], bump, has_one = authority)]
     pub marketplace: Account<'info, Marketplace>,
     #[account(
         mut,
-        seeds = [b"listing", listing.nft_mint.as_ref()],
-        bump
-    )]
-    pub listing: Account<'info, Listing>,
-    #[account(
-        seeds = [b"auction", listing.key().as_ref()],
+        close = authority,
+        seeds = [b"role", role_assignment.holder.as_ref(), &[role_assignment.role as u8]],
         bump
     )]
-    pub auction: Account<'info, Auction>,
+    pub role_assignment: Account<'info, RoleAssignment>,
     #[account(mut)]
-    pub seller_payment_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub seller_nft_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub fee_collector_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub winner_nft_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub escrow_nft_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub escrow_payment_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct CancelListing<'info> {
+pub struct ModerateListing<'info> {
     #[account(
         mut,
         seeds = [b"listing", listing.nft_mint.as_ref()],
         bump
     )]
     pub listing: Account<'info, Listing>,
-    /// CHECK: Optional auction account
+    #[account(seeds = [b"auction", listing.key().as_ref()], bump)]
     pub auction: Option<Account<'info, Auction>>,
-    pub seller: Signer<'info>,
-    #[account(mut)]
+    #[account(
+        seeds = [b"role", holder.key().as_ref(), &[role_assignment.role as u8]],
+        bump,
+        has_one = holder
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    pub holder: Signer<'info>,
+    #[account(mut, token::mint = listing.nft_mint, token::authority = listing.seller)]
     pub seller_nft_account: Account<'info, TokenAccount>,
-    #[account(mut)]
+    #[account(
+        mut,
+        associated_token::mint = listing.nft_mint,
+        associated_token::authority = listing
+    )]
     pub escrow_nft_account: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
 }
 
-#[derive(Accounts)]
-pub struct AdminAction<'info> {
-    #[account(mut, seeds = [b"marketplace"], bump, has_one = authority)]
-    pub marketplace: Account<'info, Marketplace>,
-    pub authority: Signer<'info>,
-}
-
 // ============ Events ============
 
 #[event]
@@ -658,6 +3541,46 @@ pub struct ListingCreated {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct UsdListingCreated {
+    pub listing: Pubkey,
+    pub payment_mint: Pubkey,
+    pub usd_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ListingPricesSet {
+    pub listing: Pubkey,
+    pub prices: Vec<MintPrice>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BuyerRestrictionSet {
+    pub listing: Pubkey,
+    pub restriction: Option<BuyerRestriction>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokenGateSet {
+    pub listing: Pubkey,
+    pub token_gate: Option<TokenGate>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SwapPayment {
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub swap_program: Pubkey,
+    pub source_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct ListingSold {
     pub listing: Pubkey,
@@ -669,6 +3592,46 @@ pub struct ListingSold {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct SweepExecuted {
+    pub buyer: Pubkey,
+    pub payment_mint: Pubkey,
+    pub items_bought: u8,
+    pub items_requested: u8,
+    pub total_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CrossChainPurchaseCompleted {
+    pub route_id: [u8; 32],
+    pub source_chain: u64,
+    pub listing: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CrossChainPurchaseFailed {
+    pub route_id: [u8; 32],
+    pub source_chain: u64,
+    pub listing: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CrossChainPurchaseRefunded {
+    pub route_id: [u8; 32],
+    pub source_chain: u64,
+    pub listing: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct BidPlaced {
     pub listing: Pubkey,
@@ -678,6 +3641,14 @@ pub struct BidPlaced {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct TradingRewardsPaid {
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct ListingCancelled {
     pub listing: Pubkey,
@@ -686,6 +3657,117 @@ pub struct ListingCancelled {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct ListingExpired {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub nft_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeChangeProposed {
+    pub current_fee: u16,
+    pub new_fee: u16,
+    pub execute_after: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeChangeExecuted {
+    pub old_fee: u16,
+    pub new_fee: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeChangeCancelled {
+    pub new_fee: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralPaid {
+    pub referrer: Pubkey,
+    pub listing: Pubkey,
+    pub payment_mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferrerRegistered {
+    pub referrer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferrerApprovalUpdated {
+    pub referrer: Pubkey,
+    pub approved: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeApplied {
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub base_fee_bps: u16,
+    pub applied_fee_bps: u16,
+    pub discount: FeeDiscount,
+    pub platform_fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeCollectorUpdated {
+    pub old_fee_collector: Pubkey,
+    pub new_fee_collector: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub role: Role,
+    pub holder: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub holder: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub version: u8,
+    pub timestamp: i64,
+}
+
 // ============ Errors ============
 
 #[error_code]
@@ -716,4 +3798,112 @@ pub enum ErrorCode {
     DurationTooLong,
     #[msg("Fee too high")]
     FeeTooHigh,
+    #[msg("Rewards rate too high")]
+    RewardsRateTooHigh,
+    #[msg("Rewards vault is required for this sale")]
+    RewardsVaultRequired,
+    #[msg("Winner rewards account is required for this sale")]
+    RewardsAccountRequired,
+    #[msg("Rewards account does not belong to the recipient")]
+    RewardsAccountMismatch,
+    #[msg("Invalid sweep accounts")]
+    InvalidSweepAccounts,
+    #[msg("Listing is not available")]
+    ListingUnavailable,
+    #[msg("Sweep exceeds max total price")]
+    SweepBudgetExceeded,
+    #[msg("No listings were bought")]
+    NothingSwept,
+    #[msg("Cross-chain vault does not hold the bridged payment")]
+    InsufficientCrossChainPayment,
+    #[msg("Cross-chain purchase is not refundable")]
+    NotRefundable,
+    #[msg("Invalid authority")]
+    InvalidAuthority,
+    #[msg("Not the pending authority")]
+    NotPendingAuthority,
+    #[msg("No pending authority")]
+    NoPendingAuthority,
+    #[msg("Signer does not hold the required role")]
+    MissingRole,
+    #[msg("No pending fee change")]
+    NoPendingFee,
+    #[msg("Fee change timelock is still active")]
+    TimelockActive,
+    #[msg("Invalid fee change delay")]
+    InvalidFeeDelay,
+    #[msg("Invalid fee collector")]
+    InvalidFeeCollector,
+    #[msg("Winner NFT account does not belong to the winning bidder")]
+    InvalidWinnerAccount,
+    #[msg("Discount too high")]
+    DiscountTooHigh,
+    #[msg("NFT metadata is required for a collection fee")]
+    MetadataRequired,
+    #[msg("Invalid metadata account")]
+    InvalidMetadata,
+    #[msg("NFT is not part of a verified collection")]
+    CollectionNotVerified,
+    #[msg("NFT is not part of this collection")]
+    CollectionMismatch,
+    #[msg("OGUN account is not in the fee schedule mint")]
+    InvalidOgunAccount,
+    #[msg("Stake account does not belong to the buyer's OGUN stake")]
+    InvalidStakeAccount,
+    #[msg("Referrer is not approved")]
+    ReferrerNotApproved,
+    #[msg("Referrer payment account is required")]
+    ReferrerAccountRequired,
+    #[msg("Referrer payment account does not match the referrer or payment mint")]
+    InvalidReferrerAccount,
+    #[msg("Referral share too high")]
+    ReferralShareTooHigh,
+    #[msg("Stats account is for a different payment mint")]
+    InvalidStatsAccount,
+    #[msg("Account is not owned by this program")]
+    InvalidMigrationAccount,
+    #[msg("Listing has not expired yet")]
+    ListingNotExpired,
+    #[msg("Payment mint has no price feed")]
+    PriceFeedRequired,
+    #[msg("Invalid price feed settings")]
+    InvalidPriceFeedConfig,
+    #[msg("Oracle account is not the payment mint's price feed or has no verified price")]
+    InvalidPriceOracle,
+    #[msg("Oracle price is stale")]
+    StaleOraclePrice,
+    #[msg("Oracle confidence interval is too wide")]
+    OracleConfidenceTooWide,
+    #[msg("Price conversion overflow")]
+    PriceOverflow,
+    #[msg("Price exceeds the buyer's maximum")]
+    PriceAboveMax,
+    #[msg("Listing is not priced in USD")]
+    NotUsdPriced,
+    #[msg("Listing is priced in USD; use buy_usd")]
+    UsdPriced,
+    #[msg("Listing does not accept this payment mint")]
+    PaymentMintNotAccepted,
+    #[msg("Too many payment mints")]
+    TooManyPaymentMints,
+    #[msg("Payment mint is listed twice")]
+    DuplicatePaymentMint,
+    #[msg("Swap program and source account are required")]
+    SwapAccountsRequired,
+    #[msg("Swap returned less than the minimum output")]
+    SwapOutputTooLow,
+    #[msg("Listing is reserved for other buyers")]
+    BuyerNotAllowed,
+    #[msg("USD listings can only be reserved for a single buyer")]
+    AllowlistNotSupported,
+    #[msg("Buyer does not hold the listing's gate token")]
+    TokenGateNotMet,
+    #[msg("Invalid token gate")]
+    InvalidTokenGate,
+    #[msg("Oracle price update is for a different Pyth feed")]
+    OracleFeedMismatch,
+    #[msg("Refund account does not belong to the previous bidder")]
+    InvalidPreviousBidderAccount,
+    #[msg("USD price must be greater than 0")]
+    InvalidUsdPrice,
 }
@@ -0,0 +1,50 @@
+//! Migrating a pre-versioning marketplace account
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, AnchorSerialize, Discriminator};
+use soundchain_marketplace::{ErrorCode, Marketplace, DEFAULT_FEE_DELAY, MARKETPLACE_SPACE, MARKETPLACE_VERSION};
+
+const NOW: i64 = 1_700_000_000;
+
+/// Original layout, zero-padded the way `migrate_marketplace` grows it
+fn legacy_marketplace() -> Marketplace {
+    let mut data = Marketplace::DISCRIMINATOR.to_vec();
+    Pubkey::new_unique().serialize(&mut data).unwrap(); // authority
+    Pubkey::new_unique().serialize(&mut data).unwrap(); // fee_collector
+    250u16.serialize(&mut data).unwrap(); // platform_fee
+    12u64.serialize(&mut data).unwrap(); // total_listings
+    7u64.serialize(&mut data).unwrap(); // total_sales
+    false.serialize(&mut data).unwrap(); // paused
+    data.resize(MARKETPLACE_SPACE, 0);
+    Marketplace::try_deserialize(&mut &data[..]).unwrap()
+}
+
+#[test]
+fn migration_turns_on_the_fee_timelock() {
+    let mut marketplace = legacy_marketplace();
+    assert_eq!(marketplace.fee_delay, 0);
+
+    marketplace.upgrade();
+    assert_eq!(marketplace.version, MARKETPLACE_VERSION);
+    assert_eq!(marketplace.fee_delay, DEFAULT_FEE_DELAY);
+
+    marketplace.propose_fee(500, NOW);
+    assert_eq!(marketplace.execute_fee(NOW).unwrap_err(), ErrorCode::TimelockActive.into());
+    assert_eq!(
+        marketplace.execute_fee(NOW + DEFAULT_FEE_DELAY - 1).unwrap_err(),
+        ErrorCode::TimelockActive.into()
+    );
+    assert_eq!(marketplace.platform_fee, 250);
+
+    assert_eq!(marketplace.execute_fee(NOW + DEFAULT_FEE_DELAY).unwrap(), 500);
+    assert_eq!(marketplace.platform_fee, 500);
+    assert_eq!(marketplace.pending_fee, None);
+}
+
+#[test]
+fn migration_keeps_a_configured_fee_delay() {
+    let mut marketplace = legacy_marketplace();
+    marketplace.fee_delay = 86_400;
+    marketplace.upgrade();
+    assert_eq!(marketplace.fee_delay, 86_400);
+}
@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("Am6bxddU6Hmz9D9tsRaxCVWcjRAL2Td1NNaLjmAk64GG");
+
+/// Fixed-point scale of `Pool::rate`
+pub const RATE_SCALE: u64 = 1_000_000_000;
+
+/// Mock swap pool for local testing
+///
+/// Stands in for an AMM behind the marketplace's `buy_with_swap`. Each pool
+/// swaps `mint_in` for `mint_out` at a fixed rate, paying out of a vault that
+/// is funded with a plain token transfer.
+///
+/// Not deployed outside localnet.
+#[program]
+pub mod soundchain_mock_swap {
+    use super::*;
+
+    /// Create a pool paying `amount_in * rate / RATE_SCALE` of `mint_out`
+    pub fn create_pool(ctx: Context<CreatePool>, rate: u64) -> Result<()> {
+        require!(rate > 0, ErrorCode::InvalidRate);
+
+        let pool = &mut ctx.accounts.pool;
+        pool.mint_in = ctx.accounts.mint_in.key();
+        pool.mint_out = ctx.accounts.mint_out.key();
+        pool.rate = rate;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    /// Swap `amount_in` of `mint_in` for at least `min_amount_out` of `mint_out`
+    ///
+    /// Matches the marketplace swap interface: `authority`, `source`,
+    /// `destination` and `token_program` come first, then the pool accounts.
+    pub fn swap(ctx: Context<Swap>, amount_in: u64, min_amount_out: u64) -> Result<()> {
+        let pool = &ctx.accounts.pool;
+        let amount_out = u64::try_from(amount_in as u128 * pool.rate as u128 / RATE_SCALE as u128)
+            .map_err(|_| ErrorCode::Overflow)?;
+        require!(amount_out > 0, ErrorCode::ZeroOutput);
+        require!(amount_out >= min_amount_out, ErrorCode::SlippageExceeded);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.source.to_account_info(),
+                    to: ctx.accounts.vault_in.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount_in,
+        )?;
+
+        let seeds = &[b"pool".as_ref(), pool.mint_in.as_ref(), pool.mint_out.as_ref(), &[pool.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_out.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount_out,
+        )?;
+
+        emit!(Swapped {
+            pool: pool.key(),
+            authority: ctx.accounts.authority.key(),
+            amount_in,
+            amount_out,
+        });
+
+        Ok(())
+    }
+}
+
+// ============ Accounts ============
+
+#[account]
+pub struct Pool {
+    pub mint_in: Pubkey,
+    pub mint_out: Pubkey,
+    pub rate: u64,
+    pub bump: u8,
+}
+
+// ============ Contexts ============
+
+#[derive(Accounts)]
+pub struct CreatePool<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 32 + 8 + 1,
+        seeds = [b"pool", mint_in.key().as_ref(), mint_out.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    pub mint_in: Account<'info, Mint>,
+    pub mint_out: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint_in,
+        associated_token::authority = pool
+    )]
+    pub vault_in: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint_out,
+        associated_token::authority = pool
+    )]
+    pub vault_out: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, token::mint = pool.mint_in, token::authority = authority)]
+    pub source: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = pool.mint_out)]
+    pub destination: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    #[account(seeds = [b"pool", pool.mint_in.as_ref(), pool.mint_out.as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut, associated_token::mint = pool.mint_in, associated_token::authority = pool)]
+    pub vault_in: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = pool.mint_out, associated_token::authority = pool)]
+    pub vault_out: Account<'info, TokenAccount>,
+}
+
+// ============ Events ============
+
+#[event]
+pub struct Swapped {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+// ============ Errors ============
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Rate must be positive")]
+    InvalidRate,
+    #[msg("Swap output overflows")]
+    Overflow,
+    #[msg("Swap output rounds to zero")]
+    ZeroOutput,
+    #[msg("Swap output is below the minimum")]
+    SlippageExceeded,
+}
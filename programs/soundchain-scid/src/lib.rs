@@ -1,7 +1,22 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
 
-declare_id!("SCidxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx");
+declare_id!("64Zkf47nNZW521g8k1dPtDrGn7pNo7itZeQxviXvUFqZ");
+
+/// Max registration fee (0.1 SOL)
+pub const MAX_REGISTRATION_FEE: u64 = 100_000_000;
+
+/// Fee change timelock bounds (24 hours - 30 days), 48 hours by default
+pub const DEFAULT_FEE_DELAY: i64 = 172_800;
+pub const MIN_FEE_DELAY: i64 = 86_400;
+pub const MAX_FEE_DELAY: i64 = 2_592_000;
+
+/// Current account layout versions; accounts created before versioning read as 0
+pub const REGISTRY_VERSION: u8 = 1;
+pub const SCID_RECORD_VERSION: u8 = 1;
+
+/// Account sizes for the current layouts
+pub const REGISTRY_SPACE: usize = 8 + 32 + 32 + 8 + 8 + 1 + 33 + 9 + 8 + 8 + 1;
+pub const SCID_RECORD_SPACE: usize = 8 + 28 + 32 + 8 + 32 + 68 + 8 + 2 + 4 + 8 + 1 + 1 + 2 + 32 + 1;
 
 /// SoundChain SCid Registry for Solana
 ///
@@ -22,6 +37,11 @@ pub mod soundchain_scid {
         registry.registration_fee = 1_000_000; // 0.001 SOL (1 million lamports)
         registry.total_registrations = 0;
         registry.paused = false;
+        registry.pending_authority = None;
+        registry.pending_fee = None;
+        registry.fee_change_eta = 0;
+        registry.fee_delay = DEFAULT_FEE_DELAY;
+        registry.version = REGISTRY_VERSION;
         Ok(())
     }
 
@@ -72,6 +92,7 @@ pub mod soundchain_scid {
         scid_record.registered_at = Clock::get()?.unix_timestamp;
         scid_record.active = true;
         scid_record.cross_chain_verified = false;
+        scid_record.version = SCID_RECORD_VERSION;
 
         registry.total_registrations += 1;
 
@@ -108,12 +129,13 @@ pub mod soundchain_scid {
         Ok(())
     }
 
-    /// Verify cross-chain registration (called by ZetaChain connector)
+    /// Verify cross-chain registration (cross-chain verifier only)
     pub fn verify_cross_chain(
         ctx: Context<VerifyCrossChain>,
         source_chain: u16,
         source_tx_hash: [u8; 32],
     ) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::CrossChainVerifier)?;
         let scid_record = &mut ctx.accounts.scid_record;
         require!(scid_record.active, ErrorCode::ScidInactive);
 
@@ -130,14 +152,16 @@ pub mod soundchain_scid {
         Ok(())
     }
 
-    /// Revoke SCid (owner or admin only)
+    /// Revoke SCid (owner or moderator only)
     pub fn revoke(ctx: Context<Revoke>) -> Result<()> {
         let scid_record = &mut ctx.accounts.scid_record;
-        let registry = &ctx.accounts.registry;
 
         let is_owner = scid_record.owner == ctx.accounts.authority.key();
-        let is_admin = registry.authority == ctx.accounts.authority.key();
-        require!(is_owner || is_admin, ErrorCode::NotAuthorized);
+        let is_moderator = ctx.accounts.role_assignment.as_ref().is_some_and(|role_assignment| {
+            role_assignment.role == Role::Moderator
+                && role_assignment.holder == ctx.accounts.authority.key()
+        });
+        require!(is_owner || is_moderator, ErrorCode::NotAuthorized);
 
         scid_record.active = false;
 
@@ -150,19 +174,262 @@ pub mod soundchain_scid {
         Ok(())
     }
 
-    /// Update registration fee (admin only)
-    pub fn set_fee(ctx: Context<AdminAction>, new_fee: u64) -> Result<()> {
+    /// Propose a new registration fee (fee manager only)
+    ///
+    /// Takes effect through `execute_fee` once `fee_delay` has passed.
+    pub fn propose_fee(ctx: Context<RoleAction>, new_fee: u64) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::FeeManager)?;
+        require!(new_fee <= MAX_REGISTRATION_FEE, ErrorCode::FeeTooHigh);
+
+        let now = Clock::get()?.unix_timestamp;
+        let registry = &mut ctx.accounts.registry;
+        registry.propose_fee(new_fee, now);
+
+        emit!(FeeChangeProposed {
+            current_fee: registry.registration_fee,
+            new_fee,
+            execute_after: registry.fee_change_eta,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Apply a proposed registration fee after the timelock (fee manager only)
+    pub fn execute_fee(ctx: Context<RoleAction>) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::FeeManager)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let registry = &mut ctx.accounts.registry;
+        let old_fee = registry.registration_fee;
+        let new_fee = registry.execute_fee(now)?;
+
+        emit!(FeeChangeExecuted {
+            old_fee,
+            new_fee,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel a proposed registration fee (fee manager only)
+    pub fn cancel_fee(ctx: Context<RoleAction>) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::FeeManager)?;
+
+        let registry = &mut ctx.accounts.registry;
+        let new_fee = registry.pending_fee.take().ok_or(ErrorCode::NoPendingFee)?;
+        registry.fee_change_eta = 0;
+
+        emit!(FeeChangeCancelled {
+            new_fee,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Update the fee change timelock (admin only)
+    pub fn set_fee_delay(ctx: Context<AdminAction>, fee_delay: i64) -> Result<()> {
+        require!(
+            (MIN_FEE_DELAY..=MAX_FEE_DELAY).contains(&fee_delay),
+            ErrorCode::InvalidFeeDelay
+        );
         let registry = &mut ctx.accounts.registry;
-        registry.registration_fee = new_fee;
+        registry.fee_delay = fee_delay;
         Ok(())
     }
 
-    /// Pause/unpause registry (admin only)
-    pub fn set_paused(ctx: Context<AdminAction>, paused: bool) -> Result<()> {
+    /// Propose a new registry authority (admin only)
+    ///
+    /// The handover completes when the proposed key calls `accept_authority`.
+    pub fn propose_authority(ctx: Context<AdminAction>, new_authority: Pubkey) -> Result<()> {
+        require!(new_authority != Pubkey::default(), ErrorCode::InvalidAuthority);
+        let registry = &mut ctx.accounts.registry;
+        registry.pending_authority = Some(new_authority);
+
+        emit!(AuthorityProposed {
+            authority: registry.authority,
+            pending_authority: new_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Accept a proposed authority handover (pending authority only)
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        let previous_authority = registry.authority;
+        registry.authority = ctx.accounts.pending_authority.key();
+        registry.pending_authority = None;
+
+        emit!(AuthorityTransferred {
+            previous_authority,
+            new_authority: registry.authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel a pending authority handover (admin only)
+    pub fn cancel_authority_transfer(ctx: Context<AdminAction>) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        let pending_authority = registry
+            .pending_authority
+            .take()
+            .ok_or(ErrorCode::NoPendingAuthority)?;
+
+        emit!(AuthorityTransferCancelled {
+            authority: registry.authority,
+            pending_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Pause/unpause registry (pauser only)
+    pub fn set_paused(ctx: Context<RoleAction>, paused: bool) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::Pauser)?;
         let registry = &mut ctx.accounts.registry;
         registry.paused = paused;
         Ok(())
     }
+
+    /// Update registration fee collector (treasury manager only)
+    pub fn set_fee_collector(ctx: Context<RoleAction>, new_collector: Pubkey) -> Result<()> {
+        require_role(&ctx.accounts.role_assignment, Role::TreasuryManager)?;
+        let registry = &mut ctx.accounts.registry;
+        registry.fee_collector = new_collector;
+        Ok(())
+    }
+
+    /// Grant a role to a key (admin only)
+    pub fn grant_role(ctx: Context<GrantRole>, role: Role, holder: Pubkey) -> Result<()> {
+        let role_assignment = &mut ctx.accounts.role_assignment;
+        role_assignment.role = role;
+        role_assignment.holder = holder;
+
+        emit!(RoleGranted {
+            role,
+            holder,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Revoke a role (admin only)
+    pub fn revoke_role(ctx: Context<RevokeRole>) -> Result<()> {
+        emit!(RoleRevoked {
+            role: ctx.accounts.role_assignment.role,
+            holder: ctx.accounts.role_assignment.holder,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Upgrade the registry to the current layout, growing it if needed
+    pub fn migrate_registry(ctx: Context<MigrateRegistry>) -> Result<()> {
+        migrate_account(
+            &ctx.accounts.registry,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            REGISTRY_SPACE,
+            Registry::upgrade,
+        )?;
+
+        emit!(AccountMigrated {
+            account: ctx.accounts.registry.key(),
+            version: REGISTRY_VERSION,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Upgrade an SCid record to the current layout, growing it if needed
+    pub fn migrate_scid_record(ctx: Context<MigrateAccount>) -> Result<()> {
+        migrate_account(
+            &ctx.accounts.account,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            SCID_RECORD_SPACE,
+            |scid_record: &mut ScidRecord| scid_record.version = SCID_RECORD_VERSION,
+        )?;
+
+        emit!(AccountMigrated {
+            account: ctx.accounts.account.key(),
+            version: SCID_RECORD_VERSION,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+// ============ Helpers ============
+
+/// Require a role assignment to grant `role`
+fn require_role(role_assignment: &RoleAssignment, role: Role) -> Result<()> {
+    require!(role_assignment.role == role, ErrorCode::MissingRole);
+    Ok(())
+}
+
+/// Grow a program account to `space`, topping up rent from `payer`, and rewrite it
+/// with `upgrade` applied. Bytes past the serialized data are zeroed so fields
+/// appended by later layouts start out empty.
+fn migrate_account<'info, T>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+    upgrade: impl FnOnce(&mut T),
+) -> Result<()>
+where
+    T: AccountSerialize + AccountDeserialize + Owner,
+{
+    require_keys_eq!(*account.owner, T::owner(), ErrorCode::InvalidMigrationAccount);
+
+    if account.data_len() < space {
+        let top_up = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+        if top_up > 0 {
+            let cpi_context = CpiContext::new(
+                system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            );
+            anchor_lang::system_program::transfer(cpi_context, top_up)?;
+        }
+        account.realloc(space, true)?;
+    }
+
+    // Checks the discriminator; legacy layouts read the new trailing fields as zero
+    let mut data = T::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    upgrade(&mut data);
+
+    let mut buf = Vec::with_capacity(space);
+    data.try_serialize(&mut buf)?;
+    let mut dst = account.try_borrow_mut_data()?;
+    dst[..buf.len()].copy_from_slice(&buf);
+    dst[buf.len()..].fill(0);
+    Ok(())
+}
+
+// ============ Enums ============
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Pauser,
+    FeeManager,
+    TreasuryManager,
+    CrossChainVerifier,
+    Moderator,
 }
 
 // ============ Accounts ============
@@ -174,6 +441,42 @@ pub struct Registry {
     pub registration_fee: u64,
     pub total_registrations: u64,
     pub paused: bool,
+    pub pending_authority: Option<Pubkey>,
+    pub pending_fee: Option<u64>,
+    pub fee_change_eta: i64, // When pending_fee can be executed
+    pub fee_delay: i64,      // Fee change timelock in seconds
+    pub version: u8,         // New fields go after this
+}
+
+impl Registry {
+    /// Bring a migrated account to the current layout
+    ///
+    /// Accounts created before the fee timelock read `fee_delay` as 0, and
+    /// before the fee cap may charge more than `MAX_REGISTRATION_FEE`.
+    pub fn upgrade(&mut self) {
+        self.version = REGISTRY_VERSION;
+        if self.fee_delay == 0 {
+            self.fee_delay = DEFAULT_FEE_DELAY;
+        }
+        self.registration_fee = self.registration_fee.min(MAX_REGISTRATION_FEE);
+    }
+
+    /// Queue `new_fee` to apply once `fee_delay` has passed
+    pub fn propose_fee(&mut self, new_fee: u64, now: i64) {
+        self.pending_fee = Some(new_fee);
+        self.fee_change_eta = now + self.fee_delay;
+    }
+
+    /// Apply the pending fee if its timelock has passed, returning it
+    pub fn execute_fee(&mut self, now: i64) -> Result<u64> {
+        let new_fee = self.pending_fee.ok_or(ErrorCode::NoPendingFee)?;
+        require!(now >= self.fee_change_eta, ErrorCode::TimelockActive);
+
+        self.registration_fee = new_fee;
+        self.pending_fee = None;
+        self.fee_change_eta = 0;
+        Ok(new_fee)
+    }
 }
 
 #[account]
@@ -191,6 +494,13 @@ pub struct ScidRecord {
     pub cross_chain_verified: bool,
     pub source_chain: u16,
     pub source_tx_hash: [u8; 32],
+    pub version: u8, // New fields go after this
+}
+
+#[account]
+pub struct RoleAssignment {
+    pub role: Role,
+    pub holder: Pubkey,
 }
 
 // ============ Contexts ============
@@ -200,7 +510,7 @@ pub struct Initialize<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + 32 + 32 + 8 + 8 + 1,
+        space = REGISTRY_SPACE,
         seeds = [b"registry"],
         bump
     )]
@@ -218,15 +528,15 @@ pub struct Register<'info> {
     #[account(
         init,
         payer = owner,
-        space = 8 + 28 + 32 + 8 + 32 + 68 + 8 + 2 + 4 + 8 + 1 + 1 + 2 + 32,
+        space = SCID_RECORD_SPACE,
         seeds = [b"scid", scid.as_bytes()],
         bump
     )]
     pub scid_record: Account<'info, ScidRecord>,
     #[account(mut)]
     pub owner: Signer<'info>,
-    /// CHECK: Fee collector account
-    #[account(mut)]
+    /// CHECK: Receives the registration fee; the fee collector config PDA in deployments
+    #[account(mut, address = registry.fee_collector @ ErrorCode::InvalidFeeCollector)]
     pub fee_collector: AccountInfo<'info>,
     pub system_program: Program<'info, System>,
 }
@@ -244,7 +554,13 @@ pub struct VerifyCrossChain<'info> {
     pub registry: Account<'info, Registry>,
     #[account(mut)]
     pub scid_record: Account<'info, ScidRecord>,
-    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"role", holder.key().as_ref(), &[role_assignment.role as u8]],
+        bump,
+        has_one = holder
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    pub holder: Signer<'info>,
 }
 
 #[derive(Accounts)]
@@ -254,6 +570,12 @@ pub struct Revoke<'info> {
     #[account(mut)]
     pub scid_record: Account<'info, ScidRecord>,
     pub authority: Signer<'info>,
+    /// Moderator role, when revoking someone else's SCid
+    #[account(
+        seeds = [b"role", authority.key().as_ref(), &[Role::Moderator as u8]],
+        bump
+    )]
+    pub role_assignment: Option<Account<'info, RoleAssignment>>,
 }
 
 #[derive(Accounts)]
@@ -263,6 +585,84 @@ pub struct AdminAction<'info> {
     pub authority: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct RoleAction<'info> {
+    #[account(mut, seeds = [b"registry"], bump)]
+    pub registry: Account<'info, Registry>,
+    #[account(
+        seeds = [b"role", holder.key().as_ref(), &[role_assignment.role as u8]],
+        bump,
+        has_one = holder
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    pub holder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(role: Role, holder: Pubkey)]
+pub struct GrantRole<'info> {
+    #[account(seeds = [b"registry"], bump, has_one = authority)]
+    pub registry: Account<'info, Registry>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 1 + 32,
+        seeds = [b"role", holder.as_ref(), &[role as u8]],
+        bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeRole<'info> {
+    #[account(seeds = [b"registry"], bump, has_one = authority)]
+    pub registry: Account<'info, Registry>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"role", role_assignment.holder.as_ref(), &[role_assignment.role as u8]],
+        bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateRegistry<'info> {
+    /// CHECK: Legacy layouts may not deserialize; checked and rewritten in migrate_account
+    #[account(mut, seeds = [b"registry"], bump)]
+    pub registry: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// CHECK: Owner and discriminator are checked in migrate_account
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump,
+        constraint = registry.pending_authority == Some(pending_authority.key()) @ ErrorCode::NotPendingAuthority
+    )]
+    pub registry: Account<'info, Registry>,
+    pub pending_authority: Signer<'info>,
+}
+
 // ============ Events ============
 
 #[event]
@@ -296,6 +696,69 @@ pub struct ScidRevoked {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct FeeChangeProposed {
+    pub current_fee: u64,
+    pub new_fee: u64,
+    pub execute_after: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeChangeExecuted {
+    pub old_fee: u64,
+    pub new_fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeChangeCancelled {
+    pub new_fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub role: Role,
+    pub holder: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub holder: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub version: u8,
+    pub timestamp: i64,
+}
+
 // ============ Errors ============
 
 #[error_code]
@@ -320,4 +783,24 @@ pub enum ErrorCode {
     NotOwner,
     #[msg("Not authorized")]
     NotAuthorized,
+    #[msg("Invalid authority")]
+    InvalidAuthority,
+    #[msg("Not the pending authority")]
+    NotPendingAuthority,
+    #[msg("No pending authority")]
+    NoPendingAuthority,
+    #[msg("Signer does not hold the required role")]
+    MissingRole,
+    #[msg("No pending fee change")]
+    NoPendingFee,
+    #[msg("Fee change timelock is still active")]
+    TimelockActive,
+    #[msg("Invalid fee change delay")]
+    InvalidFeeDelay,
+    #[msg("Fee too high")]
+    FeeTooHigh,
+    #[msg("Account is not owned by this program")]
+    InvalidMigrationAccount,
+    #[msg("Invalid fee collector")]
+    InvalidFeeCollector,
 }
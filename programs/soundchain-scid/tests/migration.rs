@@ -0,0 +1,46 @@
+//! Migrating a pre-versioning registry account
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, AnchorSerialize, Discriminator};
+use soundchain_scid::{
+    ErrorCode, Registry, DEFAULT_FEE_DELAY, MAX_REGISTRATION_FEE, REGISTRY_SPACE, REGISTRY_VERSION,
+};
+
+const NOW: i64 = 1_700_000_000;
+
+/// Original layout, zero-padded the way `migrate_registry` grows it
+fn legacy_registry(registration_fee: u64) -> Registry {
+    let mut data = Registry::DISCRIMINATOR.to_vec();
+    Pubkey::new_unique().serialize(&mut data).unwrap(); // authority
+    Pubkey::new_unique().serialize(&mut data).unwrap(); // fee_collector
+    registration_fee.serialize(&mut data).unwrap();
+    3u64.serialize(&mut data).unwrap(); // total_registrations
+    false.serialize(&mut data).unwrap(); // paused
+    data.resize(REGISTRY_SPACE, 0);
+    Registry::try_deserialize(&mut &data[..]).unwrap()
+}
+
+#[test]
+fn migration_turns_on_the_fee_timelock() {
+    let mut registry = legacy_registry(10_000_000);
+    assert_eq!(registry.fee_delay, 0);
+
+    registry.upgrade();
+    assert_eq!(registry.version, REGISTRY_VERSION);
+    assert_eq!(registry.fee_delay, DEFAULT_FEE_DELAY);
+    assert_eq!(registry.registration_fee, 10_000_000);
+
+    registry.propose_fee(20_000_000, NOW);
+    assert_eq!(registry.execute_fee(NOW).unwrap_err(), ErrorCode::TimelockActive.into());
+    assert_eq!(registry.registration_fee, 10_000_000);
+
+    assert_eq!(registry.execute_fee(NOW + DEFAULT_FEE_DELAY).unwrap(), 20_000_000);
+    assert_eq!(registry.registration_fee, 20_000_000);
+}
+
+#[test]
+fn migration_clamps_the_registration_fee() {
+    let mut registry = legacy_registry(5 * MAX_REGISTRATION_FEE);
+    registry.upgrade();
+    assert_eq!(registry.registration_fee, MAX_REGISTRATION_FEE);
+}
@@ -0,0 +1,615 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer as TokenTransfer};
+use anchor_spl::associated_token::AssociatedToken;
+
+declare_id!("2v9djuE2TBs1tRrMpy71VJHtNA3JD6RmjZExw7ZoTqXP");
+
+/// Precision factor for the reward-per-token accumulator
+pub const OGUN_PRECISION_FACTOR: u128 = 1_000_000_000_000;
+
+/// Slots between pool creation and the first rewarded slot
+pub const START_DELAY_SLOTS: u64 = 120;
+
+/// StakingRewards.sol counts ~2s Polygon blocks; Solana slots are ~400ms
+pub const SLOTS_PER_POLYGON_BLOCK: u64 = 5;
+
+/// Phase boundaries, in slots relative to the first rewarded slot
+pub const PHASE_ONE_SLOT: u64 = 1_250_000 * SLOTS_PER_POLYGON_BLOCK;
+pub const PHASE_TWO_SLOT: u64 = 3_125_000 * SLOTS_PER_POLYGON_BLOCK + PHASE_ONE_SLOT;
+pub const PHASE_THREE_SLOT: u64 = 10_000_000 * SLOTS_PER_POLYGON_BLOCK + PHASE_TWO_SLOT;
+pub const PHASE_FOUR_SLOT: u64 = 15_000_000 * SLOTS_PER_POLYGON_BLOCK + PHASE_THREE_SLOT;
+
+/// Emission rates are in thousandths of a token
+pub const REWARD_RATE_SCALE: u64 = 1_000;
+
+/// Rewards per slot for each phase: 32, 16, 5 and 4 tokens per Polygon block
+pub const REWARDS_PHASE_ONE: u64 = 32 * REWARD_RATE_SCALE / SLOTS_PER_POLYGON_BLOCK;
+pub const REWARDS_PHASE_TWO: u64 = 16 * REWARD_RATE_SCALE / SLOTS_PER_POLYGON_BLOCK;
+pub const REWARDS_PHASE_THREE: u64 = 5 * REWARD_RATE_SCALE / SLOTS_PER_POLYGON_BLOCK;
+pub const REWARDS_PHASE_FOUR: u64 = 4 * REWARD_RATE_SCALE / SLOTS_PER_POLYGON_BLOCK;
+
+const PHASES: [(u64, u64); 4] = [
+    (PHASE_ONE_SLOT, REWARDS_PHASE_ONE),
+    (PHASE_TWO_SLOT, REWARDS_PHASE_TWO),
+    (PHASE_THREE_SLOT, REWARDS_PHASE_THREE),
+    (PHASE_FOUR_SLOT, REWARDS_PHASE_FOUR),
+];
+
+/// SoundChain OGUN Staking Rewards for Solana
+///
+/// Port of the phased emission schedule from StakingRewards.sol.
+/// Stakers deposit OGUN into a vault and earn rewards every slot,
+/// shared pro rata by stake through a reward-per-token accumulator
+/// so each account is settled in constant time.
+///
+/// Emission is capped by the rewards supply funded into the vault
+/// and stops after phase four.
+#[program]
+pub mod soundchain_staking {
+    use super::*;
+
+    /// Initialize a staking pool for a mint (program upgrade authority only)
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let first_slot = Clock::get()?.slot + START_DELAY_SLOTS;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.authority = ctx.accounts.authority.key();
+        pool.staking_mint = ctx.accounts.staking_mint.key();
+        pool.vault = ctx.accounts.vault.key();
+        pool.token_unit = 10u64
+            .checked_pow(ctx.accounts.staking_mint.decimals as u32)
+            .ok_or(ErrorCode::MathOverflow)?;
+        pool.first_slot = first_slot;
+        pool.last_update_slot = first_slot;
+        pool.reward_per_token_stored = 0;
+        pool.total_staked = 0;
+        pool.total_rewards_supply = 0;
+        pool.total_rewards_allocated = 0;
+        Ok(())
+    }
+
+    /// Add tokens to the rewards supply
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::ZeroAmount);
+
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.funder_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.funder.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.update_rewards(Clock::get()?.slot)?;
+        pool.total_rewards_supply = pool
+            .total_rewards_supply
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(RewardsFunded {
+            pool: pool.key(),
+            funder: ctx.accounts.funder.key(),
+            amount,
+            total_rewards_supply: pool.total_rewards_supply,
+        });
+
+        Ok(())
+    }
+
+    /// Stake tokens
+    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::ZeroAmount);
+
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.staker_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.staker.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        credit_stake(
+            &mut ctx.accounts.pool,
+            &mut ctx.accounts.stake_account,
+            ctx.accounts.staker.key(),
+            amount,
+        )
+    }
+
+    /// Stake tokens on behalf of another user (e.g. streaming rewards)
+    pub fn stake_for(ctx: Context<StakeFor>, user: Pubkey, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::ZeroAmount);
+        require!(user != Pubkey::default(), ErrorCode::InvalidUser);
+
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.payer_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.payer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        credit_stake(
+            &mut ctx.accounts.pool,
+            &mut ctx.accounts.stake_account,
+            user,
+            amount,
+        )
+    }
+
+    /// Withdraw staked tokens
+    pub fn withdraw_stake(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::ZeroAmount);
+
+        let pool = &mut ctx.accounts.pool;
+        let stake_account = &mut ctx.accounts.stake_account;
+        require!(stake_account.staked_amount >= amount, ErrorCode::InsufficientStake);
+
+        pool.update_rewards(Clock::get()?.slot)?;
+        stake_account.settle(pool.reward_per_token_stored)?;
+
+        stake_account.staked_amount -= amount;
+        pool.total_staked -= amount;
+
+        let staking_mint = pool.staking_mint;
+        let seeds = &[
+            b"staking_pool",
+            staking_mint.as_ref(),
+            &[ctx.bumps.pool],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(StakeWithdrawn {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.owner.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw accrued rewards
+    pub fn withdraw_rewards(ctx: Context<Withdraw>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let stake_account = &mut ctx.accounts.stake_account;
+
+        pool.update_rewards(Clock::get()?.slot)?;
+        stake_account.settle(pool.reward_per_token_stored)?;
+
+        let amount = stake_account.rewards.min(pool.total_rewards_supply);
+        require!(amount > 0, ErrorCode::NoRewards);
+
+        stake_account.rewards -= amount;
+        pool.total_rewards_supply -= amount;
+        pool.total_rewards_allocated = pool.total_rewards_allocated.saturating_sub(amount);
+
+        let staking_mint = pool.staking_mint;
+        let seeds = &[
+            b"staking_pool",
+            staking_mint.as_ref(),
+            &[ctx.bumps.pool],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(RewardsWithdrawn {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.owner.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Checkpoint pool rewards (permissionless crank)
+    pub fn update_rewards(ctx: Context<UpdateRewards>) -> Result<()> {
+        ctx.accounts.pool.update_rewards(Clock::get()?.slot)
+    }
+
+    /// Reclaim rewards supply that has not been allocated to stakers (admin only)
+    pub fn reclaim_rewards(ctx: Context<ReclaimRewards>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.update_rewards(Clock::get()?.slot)?;
+
+        let amount = pool.total_rewards_supply - pool.total_rewards_allocated;
+        require!(amount > 0, ErrorCode::NoRewards);
+        pool.total_rewards_supply -= amount;
+
+        let staking_mint = pool.staking_mint;
+        let seeds = &[
+            b"staking_pool",
+            staking_mint.as_ref(),
+            &[ctx.bumps.pool],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(RewardsReclaimed {
+            pool: ctx.accounts.pool.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+}
+
+// ============ Helpers ============
+
+/// Total emission, in thousandths of a token (`REWARD_RATE_SCALE`), between
+/// two slots relative to the first rewarded slot
+pub fn emission_between(from: u64, to: u64) -> u128 {
+    let mut total: u128 = 0;
+    let mut cursor = from;
+    for (phase_end, rate) in PHASES {
+        if cursor >= to {
+            break;
+        }
+        if cursor < phase_end {
+            let segment_end = phase_end.min(to);
+            total += (segment_end - cursor) as u128 * rate as u128;
+            cursor = segment_end;
+        }
+    }
+    total
+}
+
+fn credit_stake(
+    pool: &mut Account<StakingPool>,
+    stake_account: &mut Account<StakeAccount>,
+    user: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    pool.update_rewards(Clock::get()?.slot)?;
+
+    if stake_account.owner == Pubkey::default() {
+        stake_account.owner = user;
+        stake_account.pool = pool.key();
+        stake_account.reward_per_token_paid = pool.reward_per_token_stored;
+    }
+    stake_account.settle(pool.reward_per_token_stored)?;
+
+    stake_account.staked_amount = stake_account
+        .staked_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.total_staked = pool
+        .total_staked
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(Staked {
+        pool: pool.key(),
+        user,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// ============ Accounts ============
+
+#[account]
+pub struct StakingPool {
+    pub authority: Pubkey,
+    pub staking_mint: Pubkey,
+    pub vault: Pubkey,
+    pub token_unit: u64,               // 10^decimals of the staking mint
+    pub first_slot: u64,
+    pub last_update_slot: u64,
+    pub reward_per_token_stored: u128, // Scaled by OGUN_PRECISION_FACTOR
+    pub total_staked: u64,
+    pub total_rewards_supply: u64,     // Funded rewards not yet withdrawn
+    pub total_rewards_allocated: u64,  // Accrued to stakers, not yet withdrawn
+}
+
+impl StakingPool {
+    /// Accrue emission since the last update into the accumulator
+    pub fn update_rewards(&mut self, slot: u64) -> Result<()> {
+        if slot <= self.last_update_slot {
+            return Ok(());
+        }
+
+        if self.total_staked > 0 {
+            let from = self.last_update_slot - self.first_slot;
+            let to = slot - self.first_slot;
+            let available = (self.total_rewards_supply - self.total_rewards_allocated) as u128;
+            let emitted = (emission_between(from, to)
+                .checked_mul(self.token_unit as u128)
+                .ok_or(ErrorCode::MathOverflow)?
+                / REWARD_RATE_SCALE as u128)
+                .min(available);
+
+            let increase = emitted * OGUN_PRECISION_FACTOR / self.total_staked as u128;
+            let distributed = increase * self.total_staked as u128 / OGUN_PRECISION_FACTOR;
+
+            self.reward_per_token_stored = self
+                .reward_per_token_stored
+                .checked_add(increase)
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.total_rewards_allocated += distributed as u64;
+        }
+
+        self.last_update_slot = slot;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct StakeAccount {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub staked_amount: u64,
+    pub rewards: u64,
+    pub reward_per_token_paid: u128,
+}
+
+impl StakeAccount {
+    /// Move rewards earned since the last checkpoint into `rewards`
+    pub fn settle(&mut self, reward_per_token: u128) -> Result<()> {
+        let earned = (self.staked_amount as u128)
+            .checked_mul(reward_per_token - self.reward_per_token_paid)
+            .ok_or(ErrorCode::MathOverflow)?
+            / OGUN_PRECISION_FACTOR;
+
+        self.rewards = self
+            .rewards
+            .checked_add(earned as u64)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.reward_per_token_paid = reward_per_token;
+        Ok(())
+    }
+}
+
+// ============ Contexts ============
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 16 + 8 + 8 + 8,
+        seeds = [b"staking_pool", staking_mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, StakingPool>,
+    pub staking_mint: Account<'info, Mint>,
+    /// May already exist: the pool address is predictable, so anyone can create its ATA first
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = staking_mint,
+        associated_token::authority = pool
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    /// Becomes the pool authority, which can reclaim unallocated rewards
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::SoundchainStaking>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key()) @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+}
+
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool", pool.staking_mint.as_ref()],
+        bump,
+        has_one = vault
+    )]
+    pub pool: Account<'info, StakingPool>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    pub funder: Signer<'info>,
+    #[account(mut, token::mint = pool.staking_mint)]
+    pub funder_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool", pool.staking_mint.as_ref()],
+        bump,
+        has_one = vault
+    )]
+    pub pool: Account<'info, StakingPool>,
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = 8 + 32 + 32 + 8 + 8 + 16,
+        seeds = [b"stake", pool.key().as_ref(), staker.key().as_ref()],
+        bump
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub staker: Signer<'info>,
+    #[account(mut, token::mint = pool.staking_mint)]
+    pub staker_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct StakeFor<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool", pool.staking_mint.as_ref()],
+        bump,
+        has_one = vault
+    )]
+    pub pool: Account<'info, StakingPool>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + 32 + 32 + 8 + 8 + 16,
+        seeds = [b"stake", pool.key().as_ref(), user.as_ref()],
+        bump
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, token::mint = pool.staking_mint)]
+    pub payer_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool", pool.staking_mint.as_ref()],
+        bump,
+        has_one = vault
+    )]
+    pub pool: Account<'info, StakingPool>,
+    #[account(
+        mut,
+        seeds = [b"stake", pool.key().as_ref(), owner.key().as_ref()],
+        bump,
+        has_one = owner
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    #[account(mut, token::mint = pool.staking_mint)]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRewards<'info> {
+    #[account(mut, seeds = [b"staking_pool", pool.staking_mint.as_ref()], bump)]
+    pub pool: Account<'info, StakingPool>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool", pool.staking_mint.as_ref()],
+        bump,
+        has_one = authority,
+        has_one = vault
+    )]
+    pub pool: Account<'info, StakingPool>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    #[account(mut, token::mint = pool.staking_mint)]
+    pub destination: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+// ============ Events ============
+
+#[event]
+pub struct Staked {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakeWithdrawn {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsWithdrawn {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsFunded {
+    pub pool: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub total_rewards_supply: u64,
+}
+
+#[event]
+pub struct RewardsReclaimed {
+    pub pool: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+// ============ Errors ============
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Amount must be greater than 0")]
+    ZeroAmount,
+    #[msg("Cannot stake for the default pubkey")]
+    InvalidUser,
+    #[msg("Withdraw amount is greater than staked amount")]
+    InsufficientStake,
+    #[msg("No rewards to withdraw")]
+    NoRewards,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Signer is not the program upgrade authority")]
+    NotUpgradeAuthority,
+}
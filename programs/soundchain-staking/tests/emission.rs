@@ -0,0 +1,88 @@
+//! Emission schedule and reward accrual
+
+use anchor_lang::prelude::Pubkey;
+use soundchain_staking::{
+    emission_between, StakeAccount, StakingPool, PHASE_FOUR_SLOT, PHASE_ONE_SLOT, PHASE_THREE_SLOT,
+    PHASE_TWO_SLOT, REWARD_RATE_SCALE,
+};
+
+const TOKEN: u64 = 1_000_000_000;
+const FIRST_SLOT: u64 = 1_000;
+
+fn pool(total_staked: u64, total_rewards_supply: u64) -> StakingPool {
+    StakingPool {
+        authority: Pubkey::new_unique(),
+        staking_mint: Pubkey::new_unique(),
+        vault: Pubkey::new_unique(),
+        token_unit: TOKEN,
+        first_slot: FIRST_SLOT,
+        last_update_slot: FIRST_SLOT,
+        reward_per_token_stored: 0,
+        total_staked,
+        total_rewards_supply,
+        total_rewards_allocated: 0,
+    }
+}
+
+fn tokens(amount: u128) -> u128 {
+    amount * REWARD_RATE_SCALE as u128
+}
+
+#[test]
+fn phases_emit_the_polygon_totals_over_five_times_the_slots() {
+    // 1.25M, 3.125M, 10M and 15M Polygon blocks at 32, 16, 5 and 4 tokens
+    assert_eq!(emission_between(0, PHASE_ONE_SLOT), tokens(40_000_000));
+    assert_eq!(emission_between(PHASE_ONE_SLOT, PHASE_TWO_SLOT), tokens(50_000_000));
+    assert_eq!(emission_between(PHASE_TWO_SLOT, PHASE_THREE_SLOT), tokens(50_000_000));
+    assert_eq!(emission_between(PHASE_THREE_SLOT, PHASE_FOUR_SLOT), tokens(60_000_000));
+    assert_eq!(emission_between(0, PHASE_FOUR_SLOT), tokens(200_000_000));
+    assert_eq!(PHASE_FOUR_SLOT, 29_375_000 * 5);
+}
+
+#[test]
+fn emission_spans_phase_boundaries_and_stops_after_phase_four() {
+    // 6.4 tokens per slot in phase one, 3.2 in phase two
+    assert_eq!(emission_between(0, 10), 64_000);
+    assert_eq!(emission_between(PHASE_ONE_SLOT - 10, PHASE_ONE_SLOT + 10), 64_000 + 32_000);
+    assert_eq!(emission_between(PHASE_FOUR_SLOT - 1, PHASE_FOUR_SLOT + 1_000), 800);
+    assert_eq!(emission_between(PHASE_FOUR_SLOT, PHASE_FOUR_SLOT + 1_000), 0);
+    assert_eq!(emission_between(50, 50), 0);
+}
+
+#[test]
+fn rewards_accrue_pro_rata_to_stakers() {
+    let mut pool = pool(100 * TOKEN, 1_000 * TOKEN);
+    pool.update_rewards(FIRST_SLOT + 10).unwrap();
+    assert_eq!(pool.total_rewards_allocated, 64 * TOKEN);
+    assert_eq!(pool.last_update_slot, FIRST_SLOT + 10);
+
+    let mut stake = StakeAccount {
+        owner: Pubkey::new_unique(),
+        pool: Pubkey::new_unique(),
+        staked_amount: 25 * TOKEN,
+        rewards: 0,
+        reward_per_token_paid: 0,
+    };
+    stake.settle(pool.reward_per_token_stored).unwrap();
+    assert_eq!(stake.rewards, 16 * TOKEN);
+
+    // Same or earlier slots change nothing
+    pool.update_rewards(FIRST_SLOT + 5).unwrap();
+    assert_eq!(pool.total_rewards_allocated, 64 * TOKEN);
+    assert_eq!(pool.last_update_slot, FIRST_SLOT + 10);
+}
+
+#[test]
+fn nothing_accrues_without_stakers_or_beyond_the_supply() {
+    let mut empty = pool(0, 1_000 * TOKEN);
+    empty.update_rewards(FIRST_SLOT + 10).unwrap();
+    assert_eq!(empty.total_rewards_allocated, 0);
+    assert_eq!(empty.reward_per_token_stored, 0);
+    assert_eq!(empty.last_update_slot, FIRST_SLOT + 10);
+
+    let mut underfunded = pool(100 * TOKEN, 10 * TOKEN);
+    underfunded.update_rewards(FIRST_SLOT + 10).unwrap();
+    assert_eq!(underfunded.total_rewards_allocated, 10 * TOKEN);
+    underfunded.update_rewards(FIRST_SLOT + 20).unwrap();
+    assert_eq!(underfunded.total_rewards_allocated, 10 * TOKEN);
+}
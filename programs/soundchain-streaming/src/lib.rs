@@ -0,0 +1,481 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer as TokenTransfer};
+use anchor_spl::associated_token::AssociatedToken;
+use soundchain_scid::ScidRecord;
+
+declare_id!("D2FCRzzZYuYmZ8cZFazq2G3hGvoAvBXYv77AHQmnCd1u");
+
+/// Length of a reward epoch in seconds (1 day)
+pub const EPOCH_DURATION: i64 = 86_400;
+
+/// SoundChain Streaming Rewards Distributor for Solana
+///
+/// Pays artists for plays, modeled on StreamingRewardsDistributor.sol.
+/// Authorized backend distributors post one allocation per SCid per epoch,
+/// and the current owner of the ScidRecord claims it from the reward vault.
+///
+/// - Per-SCid and per-epoch caps bound what a distributor can post
+/// - Allocation PDAs are keyed by (SCid, epoch), so an epoch cannot be paid twice
+/// - Posted allocations must be fully backed by the vault balance
+#[program]
+pub mod soundchain_streaming {
+    use super::*;
+
+    /// Initialize the distributor
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        max_epoch_rewards: u64,
+        max_scid_rewards_per_epoch: u64,
+    ) -> Result<()> {
+        require!(
+            max_scid_rewards_per_epoch <= max_epoch_rewards,
+            ErrorCode::InvalidCaps
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.reward_mint = ctx.accounts.reward_mint.key();
+        config.vault = ctx.accounts.vault.key();
+        config.max_epoch_rewards = max_epoch_rewards;
+        config.max_scid_rewards_per_epoch = max_scid_rewards_per_epoch;
+        config.total_allocated = 0;
+        config.total_claimed = 0;
+        config.paused = false;
+        Ok(())
+    }
+
+    /// Authorize a backend service to post allocations (admin only)
+    pub fn authorize_distributor(ctx: Context<AuthorizeDistributor>, distributor: Pubkey) -> Result<()> {
+        let authorization = &mut ctx.accounts.authorization;
+        authorization.distributor = distributor;
+        authorization.authorized_at = Clock::get()?.unix_timestamp;
+
+        emit!(DistributorAuthorized {
+            distributor,
+            timestamp: authorization.authorized_at,
+        });
+
+        Ok(())
+    }
+
+    /// Revoke a backend distributor (admin only)
+    pub fn revoke_distributor(ctx: Context<RevokeDistributor>) -> Result<()> {
+        emit!(DistributorRevoked {
+            distributor: ctx.accounts.authorization.distributor,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Post the reward allocation for an SCid in an epoch
+    pub fn post_allocation(ctx: Context<PostAllocation>, epoch: u64, amount: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(!config.paused, ErrorCode::DistributorPaused);
+        require!(ctx.accounts.scid_record.active, ErrorCode::ScidInactive);
+
+        let current_epoch = (Clock::get()?.unix_timestamp / EPOCH_DURATION) as u64;
+        require!(epoch <= current_epoch, ErrorCode::FutureEpoch);
+
+        let epoch_state = &mut ctx.accounts.epoch_state;
+        epoch_state.epoch = epoch;
+        config.allocate(epoch_state, amount, ctx.accounts.vault.amount)?;
+
+        let allocation = &mut ctx.accounts.allocation;
+        allocation.scid_record = ctx.accounts.scid_record.key();
+        allocation.epoch = epoch;
+        allocation.amount = amount;
+        allocation.claimed = false;
+        allocation.posted_by = ctx.accounts.distributor.key();
+
+        emit!(AllocationPosted {
+            scid: ctx.accounts.scid_record.scid.clone(),
+            epoch,
+            amount,
+            distributor: ctx.accounts.distributor.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Claim an epoch allocation (current SCid owner only)
+    pub fn claim(ctx: Context<Claim>, epoch: u64) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(!config.paused, ErrorCode::DistributorPaused);
+        require!(ctx.accounts.scid_record.active, ErrorCode::ScidInactive);
+        require!(
+            ctx.accounts.scid_record.owner == ctx.accounts.owner.key(),
+            ErrorCode::NotScidOwner
+        );
+
+        let amount = ctx.accounts.allocation.claim()?;
+
+        let seeds = &[b"streaming_distributor".as_ref(), &[ctx.bumps.config]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: ctx.accounts.config.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        ctx.accounts.config.total_claimed += amount;
+
+        emit!(RewardsClaimed {
+            scid: ctx.accounts.scid_record.scid.clone(),
+            owner: ctx.accounts.owner.key(),
+            epoch,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Update reward caps (admin only)
+    pub fn set_caps(
+        ctx: Context<AdminAction>,
+        max_epoch_rewards: u64,
+        max_scid_rewards_per_epoch: u64,
+    ) -> Result<()> {
+        require!(
+            max_scid_rewards_per_epoch <= max_epoch_rewards,
+            ErrorCode::InvalidCaps
+        );
+        let config = &mut ctx.accounts.config;
+        config.max_epoch_rewards = max_epoch_rewards;
+        config.max_scid_rewards_per_epoch = max_scid_rewards_per_epoch;
+        Ok(())
+    }
+
+    /// Pause/unpause distributor (admin only)
+    pub fn set_paused(ctx: Context<AdminAction>, paused: bool) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.paused = paused;
+        Ok(())
+    }
+
+    /// Withdraw the full vault balance (admin only, must be paused)
+    pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+        require!(ctx.accounts.config.paused, ErrorCode::NotPaused);
+        let amount = ctx.accounts.vault.amount;
+        require!(amount > 0, ErrorCode::ZeroAmount);
+
+        let seeds = &[b"streaming_distributor".as_ref(), &[ctx.bumps.config]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.config.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(EmergencyWithdrawn {
+            destination: ctx.accounts.destination.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+// ============ Accounts ============
+
+#[account]
+pub struct DistributorConfig {
+    pub authority: Pubkey,
+    pub reward_mint: Pubkey,
+    pub vault: Pubkey,
+    pub max_epoch_rewards: u64,
+    pub max_scid_rewards_per_epoch: u64,
+    pub total_allocated: u64,
+    pub total_claimed: u64,
+    pub paused: bool,
+}
+
+impl DistributorConfig {
+    /// Add `amount` for one SCid to `epoch_state`'s epoch, within the caps
+    pub fn allocate(&mut self, epoch_state: &mut EpochState, amount: u64, vault_balance: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::ZeroAmount);
+        require!(
+            amount <= self.max_scid_rewards_per_epoch,
+            ErrorCode::ScidCapExceeded
+        );
+
+        let epoch_total = epoch_state
+            .total_allocated
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(epoch_total <= self.max_epoch_rewards, ErrorCode::EpochCapExceeded);
+
+        // Every unclaimed allocation must be backed by the vault
+        let outstanding = self.total_allocated - self.total_claimed + amount;
+        require!(vault_balance >= outstanding, ErrorCode::InsufficientVaultBalance);
+
+        epoch_state.total_allocated = epoch_total;
+        self.total_allocated += amount;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct DistributorAuthorization {
+    pub distributor: Pubkey,
+    pub authorized_at: i64,
+}
+
+#[account]
+pub struct EpochState {
+    pub epoch: u64,
+    pub total_allocated: u64,
+}
+
+#[account]
+pub struct Allocation {
+    pub scid_record: Pubkey,
+    pub epoch: u64,
+    pub amount: u64,
+    pub claimed: bool,
+    pub posted_by: Pubkey,
+}
+
+impl Allocation {
+    /// Mark the allocation paid, returning its amount; an allocation pays out once
+    pub fn claim(&mut self) -> Result<u64> {
+        require!(!self.claimed, ErrorCode::AlreadyClaimed);
+        self.claimed = true;
+        Ok(self.amount)
+    }
+}
+
+// ============ Contexts ============
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 1,
+        seeds = [b"streaming_distributor"],
+        bump
+    )]
+    pub config: Account<'info, DistributorConfig>,
+    pub reward_mint: Account<'info, Mint>,
+    /// May already exist: the config address is predictable, so anyone can create its ATA first
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    /// Becomes the distributor authority, which authorizes backends and sets caps
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::SoundchainStreaming>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key()) @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+}
+
+#[derive(Accounts)]
+#[instruction(distributor: Pubkey)]
+pub struct AuthorizeDistributor<'info> {
+    #[account(seeds = [b"streaming_distributor"], bump, has_one = authority)]
+    pub config: Account<'info, DistributorConfig>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 8,
+        seeds = [b"distributor", distributor.as_ref()],
+        bump
+    )]
+    pub authorization: Account<'info, DistributorAuthorization>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeDistributor<'info> {
+    #[account(seeds = [b"streaming_distributor"], bump, has_one = authority)]
+    pub config: Account<'info, DistributorConfig>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"distributor", authorization.distributor.as_ref()],
+        bump
+    )]
+    pub authorization: Account<'info, DistributorAuthorization>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct PostAllocation<'info> {
+    #[account(mut, seeds = [b"streaming_distributor"], bump, has_one = vault)]
+    pub config: Account<'info, DistributorConfig>,
+    #[account(
+        seeds = [b"distributor", distributor.key().as_ref()],
+        bump
+    )]
+    pub authorization: Account<'info, DistributorAuthorization>,
+    pub scid_record: Account<'info, ScidRecord>,
+    #[account(
+        init_if_needed,
+        payer = distributor,
+        space = 8 + 8 + 8,
+        seeds = [b"epoch", epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub epoch_state: Account<'info, EpochState>,
+    #[account(
+        init,
+        payer = distributor,
+        space = 8 + 32 + 8 + 8 + 1 + 32,
+        seeds = [b"allocation", scid_record.key().as_ref(), epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub allocation: Account<'info, Allocation>,
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub distributor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct Claim<'info> {
+    #[account(mut, seeds = [b"streaming_distributor"], bump, has_one = vault)]
+    pub config: Account<'info, DistributorConfig>,
+    pub scid_record: Account<'info, ScidRecord>,
+    #[account(
+        mut,
+        seeds = [b"allocation", scid_record.key().as_ref(), epoch.to_le_bytes().as_ref()],
+        bump,
+        has_one = scid_record
+    )]
+    pub allocation: Account<'info, Allocation>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    #[account(mut, token::mint = config.reward_mint)]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct AdminAction<'info> {
+    #[account(mut, seeds = [b"streaming_distributor"], bump, has_one = authority)]
+    pub config: Account<'info, DistributorConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    #[account(
+        seeds = [b"streaming_distributor"],
+        bump,
+        has_one = authority,
+        has_one = vault
+    )]
+    pub config: Account<'info, DistributorConfig>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    #[account(mut, token::mint = config.reward_mint)]
+    pub destination: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+// ============ Events ============
+
+#[event]
+pub struct DistributorAuthorized {
+    pub distributor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DistributorRevoked {
+    pub distributor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AllocationPosted {
+    pub scid: String,
+    pub epoch: u64,
+    pub amount: u64,
+    pub distributor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsClaimed {
+    pub scid: String,
+    pub owner: Pubkey,
+    pub epoch: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmergencyWithdrawn {
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+// ============ Errors ============
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Distributor is paused")]
+    DistributorPaused,
+    #[msg("Distributor is not paused")]
+    NotPaused,
+    #[msg("Amount must be greater than 0")]
+    ZeroAmount,
+    #[msg("Per-SCid cap cannot exceed the epoch cap")]
+    InvalidCaps,
+    #[msg("Cannot post allocations for a future epoch")]
+    FutureEpoch,
+    #[msg("Per-SCid epoch cap exceeded")]
+    ScidCapExceeded,
+    #[msg("Epoch cap exceeded")]
+    EpochCapExceeded,
+    #[msg("Vault balance does not cover outstanding allocations")]
+    InsufficientVaultBalance,
+    #[msg("SCid is inactive")]
+    ScidInactive,
+    #[msg("Not the SCid owner")]
+    NotScidOwner,
+    #[msg("Allocation already claimed")]
+    AlreadyClaimed,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Signer is not the program upgrade authority")]
+    NotUpgradeAuthority,
+}
@@ -0,0 +1,86 @@
+//! Allocation caps, vault backing and the claim replay guard
+
+use anchor_lang::prelude::Pubkey;
+use soundchain_streaming::{Allocation, DistributorConfig, EpochState, ErrorCode};
+
+const TOKEN: u64 = 1_000_000_000;
+
+fn config(max_epoch_rewards: u64, max_scid_rewards_per_epoch: u64) -> DistributorConfig {
+    DistributorConfig {
+        authority: Pubkey::new_unique(),
+        reward_mint: Pubkey::new_unique(),
+        vault: Pubkey::new_unique(),
+        max_epoch_rewards,
+        max_scid_rewards_per_epoch,
+        total_allocated: 0,
+        total_claimed: 0,
+        paused: false,
+    }
+}
+
+fn epoch_state() -> EpochState {
+    EpochState { epoch: 19_000, total_allocated: 0 }
+}
+
+#[test]
+fn allocations_are_capped_per_scid_and_per_epoch() {
+    let mut config = config(250 * TOKEN, 100 * TOKEN);
+    let mut epoch = epoch_state();
+    let vault = 1_000 * TOKEN;
+
+    assert_eq!(
+        config.allocate(&mut epoch, 100 * TOKEN + 1, vault).unwrap_err(),
+        ErrorCode::ScidCapExceeded.into()
+    );
+    config.allocate(&mut epoch, 100 * TOKEN, vault).unwrap();
+    config.allocate(&mut epoch, 100 * TOKEN, vault).unwrap();
+    assert_eq!(
+        config.allocate(&mut epoch, 50 * TOKEN + 1, vault).unwrap_err(),
+        ErrorCode::EpochCapExceeded.into()
+    );
+    config.allocate(&mut epoch, 50 * TOKEN, vault).unwrap();
+    assert_eq!(epoch.total_allocated, 250 * TOKEN);
+    assert_eq!(config.total_allocated, 250 * TOKEN);
+
+    // The epoch cap is per epoch
+    let mut next_epoch = epoch_state();
+    config.allocate(&mut next_epoch, 100 * TOKEN, vault).unwrap();
+    assert_eq!(next_epoch.total_allocated, 100 * TOKEN);
+
+    assert_eq!(config.allocate(&mut next_epoch, 0, vault).unwrap_err(), ErrorCode::ZeroAmount.into());
+}
+
+#[test]
+fn allocations_must_be_backed_by_the_vault() {
+    let mut config = config(1_000 * TOKEN, 100 * TOKEN);
+    let mut epoch = epoch_state();
+
+    config.allocate(&mut epoch, 60 * TOKEN, 100 * TOKEN).unwrap();
+    assert_eq!(
+        config.allocate(&mut epoch, 60 * TOKEN, 100 * TOKEN).unwrap_err(),
+        ErrorCode::InsufficientVaultBalance.into()
+    );
+    assert_eq!(epoch.total_allocated, 60 * TOKEN);
+
+    // Once claimed, rewards have left the vault and no longer need backing
+    config.total_claimed = 60 * TOKEN;
+    config.allocate(&mut epoch, 40 * TOKEN, 40 * TOKEN).unwrap();
+    assert_eq!(
+        config.allocate(&mut epoch, 1, 40 * TOKEN).unwrap_err(),
+        ErrorCode::InsufficientVaultBalance.into()
+    );
+}
+
+#[test]
+fn an_allocation_pays_out_once() {
+    let mut allocation = Allocation {
+        scid_record: Pubkey::new_unique(),
+        epoch: 19_000,
+        amount: 42 * TOKEN,
+        claimed: false,
+        posted_by: Pubkey::new_unique(),
+    };
+    assert_eq!(allocation.claim().unwrap(), 42 * TOKEN);
+    assert!(allocation.claimed);
+    assert_eq!(allocation.claim().unwrap_err(), ErrorCode::AlreadyClaimed.into());
+}